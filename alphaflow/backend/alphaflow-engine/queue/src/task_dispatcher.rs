@@ -26,6 +26,10 @@ pub struct TaskDispatcher {
     // 改成 NodeType
     handlers: HashMap<NodeTypeId, Arc<dyn NodeType>>,
 
+    /// 传给每个任务节点的 NodeExecutionContext.globals / env，默认为空对象
+    globals: serde_json::Value,
+    env: serde_json::Value,
+
     notifier: watch::Sender<bool>,
     pub(crate) notifier_rx: Option<watch::Receiver<bool>>,
 }
@@ -38,6 +42,8 @@ impl TaskDispatcher {
             store: TaskStore::new(),
             timeout,
             handlers: HashMap::new(),
+            globals: serde_json::json!({}),
+            env: serde_json::json!({}),
             notifier,
             notifier_rx: Some(notifier_rx),
         }
@@ -53,6 +59,12 @@ impl TaskDispatcher {
         self.handlers.insert(node_name, Arc::new(node));
     }
 
+    /// 设置任务节点可以访问的 globals / env（例如时区等全局配置、白名单中的环境变量）
+    pub fn set_context(&mut self, globals: serde_json::Value, env: serde_json::Value) {
+        self.globals = globals;
+        self.env = env;
+    }
+
     pub async fn unregister_node<T: AsRef<str>>(&mut self, node_name: T) {
         if let Some(node) = self.handlers.remove(node_name.as_ref()) {
             trace!("Node {} is unregistered", node.name());
@@ -131,9 +143,10 @@ impl TaskDispatcher {
             TaskContent::Text(s) => NodeExecutionContext {
                 parameters: serde_json::json!({ "text": s }),
                 input_data: serde_json::Value::Null,
-                globals: serde_json::Value::Null,
-                env: serde_json::Value::Null,
+                globals: self.globals.clone(),
+                env: self.env.clone(),
                 pin_data: None,
+                ..Default::default()
             },
             TaskContent::Blob(bytes) => {
                 // maybe parse as JSON or keep as raw?
@@ -141,9 +154,10 @@ impl TaskDispatcher {
                 NodeExecutionContext {
                     parameters: serde_json::json!({ "blob_size": bytes.len() }),
                     input_data: serde_json::Value::Null,
-                    globals: serde_json::Value::Null,
-                    env: serde_json::Value::Null,
+                    globals: self.globals.clone(),
+                    env: self.env.clone(),
                     pin_data: None,
+                    ..Default::default()
                 }
             },
         }
@@ -63,6 +63,13 @@ pub enum Ast {
         /// Field name to extract.
         name: String,
     },
+    /// Resolves to the value bound to a variable, e.g. `$json`.
+    VariableRef {
+        /// Approximate absolute position in the parsed expression.
+        offset: usize,
+        /// Variable name without the leading `$`.
+        name: String,
+    },
     /// Extracts an index from a Vec.
     Index {
         /// Approximate absolute position in the parsed expression.
@@ -138,6 +138,8 @@ pub enum RuntimeError {
     },
     /// Encountered when an unknown function is called.
     UnknownFunction(String),
+    /// Encountered when a variable that was not provided is referenced.
+    UndefinedVariable(String),
     /// Encountered when a type of variable given to a function is invalid.
     InvalidType {
         /// Expected type.
@@ -165,6 +167,7 @@ impl fmt::Display for RuntimeError {
         use self::RuntimeError::*;
         match *self {
             UnknownFunction(ref function) => write!(fmt, "Call to undefined function {}", function),
+            UndefinedVariable(ref name) => write!(fmt, "Reference to undefined variable ${}", name),
             TooManyArguments {
                 ref expected,
                 ref actual,
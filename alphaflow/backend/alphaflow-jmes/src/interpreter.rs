@@ -227,6 +227,17 @@ pub fn interpret(data: &Arcvar, node: &Ast, ctx: &mut Context<'_>) -> SearchResu
             trace!("  -> Ast::Identity => return data clone");
             Ok(data.clone())
         }
+        Ast::VariableRef { ref name, offset } => {
+            trace!("  -> Ast::VariableRef: name={:?}", name);
+            match ctx.variables.get(name) {
+                Some(value) => Ok(value.clone()),
+                None => {
+                    ctx.offset = offset;
+                    let reason = ErrorReason::Runtime(RuntimeError::UndefinedVariable(name.clone()));
+                    Err(JmespathError::from_ctx(ctx, reason))
+                }
+            }
+        }
         Ast::Literal { ref value, .. } => {
             trace!("  -> Ast::Literal => value={:?}", value);
             Ok(value.clone())
@@ -19,6 +19,8 @@ use crate::{Arcvar, ErrorReason, JmespathError};
 pub enum Token {
     Identifier(String),
     QuotedIdentifier(String),
+    /// Variable reference such as `$json` (name without the leading `$`).
+    Variable(String),
     Number(i32),
     Literal(Arcvar),
     Dot,
@@ -115,6 +117,7 @@ impl<'a> Lexer<'a> {
                         '*' => tokens.push_back((pos, Star)),
                         '|' => tokens.push_back((pos, self.alt('|', Or, Pipe))),
                         '@' => tokens.push_back((pos, At)),
+                        '$' => tokens.push_back((pos, self.consume_variable(pos)?)),
                         ']' => tokens.push_back((pos, Rbracket)),
                         '{' => tokens.push_back((pos, Lbrace)),
                         '}' => tokens.push_back((pos, Rbrace)),
@@ -199,6 +202,21 @@ impl<'a> Lexer<'a> {
         ))
     }
 
+    // Consume variable references: "$" ( ALPHA / "_" ) *( DIGIT / ALPHA / "_" )
+    #[inline]
+    fn consume_variable(&mut self, pos: usize) -> Result<Token, JmespathError> {
+        match self.iter.next() {
+            Some((_, c)) if c.is_ascii_alphabetic() || c == '_' => match self.consume_identifier(c) {
+                Identifier(name) => Ok(Variable(name)),
+                _ => unreachable!("consume_identifier always returns an identifier"),
+            },
+            _ => {
+                let reason = ErrorReason::Parse("'$' must be followed by a variable name".to_owned());
+                Err(JmespathError::new(self.expr, pos, reason))
+            }
+        }
+    }
+
     // Consumes numbers: *"-" "0" / ( %x31-39 *DIGIT )
     #[inline]
     fn consume_number(
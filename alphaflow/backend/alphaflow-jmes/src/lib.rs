@@ -4,13 +4,13 @@ pub use crate::errors::{ErrorReason, JmespathError, RuntimeError};
 pub use crate::parser::{parse, ParseResult};
 pub use crate::runtime::Runtime;
 pub use crate::variable::Variable;
-pub use crate::expression::apply_template;
-pub use crate::jmes_runtime::compile_and_search;
+pub use crate::jmes_runtime::{compile_and_search, JmesMappingError};
 
 pub mod ast;
 pub mod functions;
 
 use serde::ser;
+use std::collections::BTreeMap;
 use std::fmt;
 
 use lazy_static::*;
@@ -24,7 +24,6 @@ mod lexer;
 mod parser;
 mod runtime;
 mod variable;
-mod expression;
 mod jmes_runtime;
 
 
@@ -382,6 +381,18 @@ impl<'a> Expression<'a> {
         interpret(&data.to_jmespath()?, &self.ast, &mut ctx)
     }
 
+    /// Same as `search`, but `$name` references in the expression resolve to
+    /// the given variables. Referencing a missing variable is a runtime error.
+    pub fn search_with_variables<T: ToJmespath>(
+        &self,
+        data: T,
+        variables: BTreeMap<String, Arcvar>,
+    ) -> SearchResult {
+        let mut ctx = Context::new(&self.expression, self.runtime);
+        ctx.variables = variables;
+        interpret(&data.to_jmespath()?, &self.ast, &mut ctx)
+    }
+
     /// Returns the JMESPath expression from which the Expression was compiled.
     ///
     /// Note that this is the same value that is returned by calling
@@ -429,6 +440,8 @@ pub struct Context<'a> {
     pub runtime: &'a Runtime,
     /// Ast offset that is currently being evaluated.
     pub offset: usize,
+    /// Values of the `$name` variables available to the expression.
+    pub variables: BTreeMap<String, Arcvar>,
 }
 
 impl<'a> Context<'a> {
@@ -439,6 +452,7 @@ impl<'a> Context<'a> {
             expression,
             runtime,
             offset: 0,
+            variables: BTreeMap::new(),
         }
     }
 }
@@ -473,6 +487,30 @@ mod test {
         );
     }
 
+    #[test]
+    fn resolves_variables_and_bracket_fields() {
+        let expr = compile("$input.items[?price > $min]").unwrap();
+        let mut variables = BTreeMap::new();
+        variables.insert(
+            "input".to_string(),
+            Variable::from_json(r#"{"items":[{"name":"a","price":1},{"name":"b","price":5}]}"#)
+                .unwrap()
+                .to_jmespath()
+                .unwrap(),
+        );
+        variables.insert("min".to_string(), Arcvar::new(Variable::Number(2.into())));
+        let result = expr.search_with_variables(Variable::Null, variables).unwrap();
+        assert_eq!(r#"[{"name":"b","price":5}]"#, result.to_string());
+
+        let expr = compile(r#"foo["bar"][0]"#).unwrap();
+        let var = Variable::from_json(r#"{"foo":{"bar":[7]}}"#).unwrap();
+        assert_eq!(Arcvar::new(Variable::Number(7.into())), expr.search(var).unwrap());
+
+        let err = compile("$missing").unwrap().search(Variable::Null).unwrap_err();
+        assert_eq!(err.reason, ErrorReason::Runtime(RuntimeError::UndefinedVariable("missing".into())));
+        assert!(compile("$ foo").is_err());
+    }
+
     #[test]
     fn can_get_expression_ast() {
         let expr = compile("foo").unwrap();
@@ -128,6 +128,10 @@ impl<'a> Parser<'a> {
                 trace!("nud => Ast::Field({:?})", value);
                 Ok(Ast::Field { name: value, offset })
             }
+            Token::Variable(name) => {
+                trace!("nud => Ast::VariableRef({:?})", name);
+                Ok(Ast::VariableRef { name, offset })
+            }
             Token::QuotedIdentifier(value) => {
                 trace!("nud => QuotedIdentifier={:?}", value);
                 match self.peek(0) {
@@ -224,6 +228,15 @@ impl<'a> Parser<'a> {
                 trace!("led => '[' operator");
                 if matches!(self.peek(0), Token::Number(_) | Token::Colon) {
                     Ok(Ast::Subexpr { offset, lhs: left, rhs: Box::new(self.parse_index()?) })
+                } else if matches!(self.peek(0), Token::QuotedIdentifier(_)) && self.peek(1) == &Token::Rbracket {
+                    // 扩展语法：foo["bar"] 等价于 foo."bar"（标准 JMESPath 中这里是语法错误）
+                    let (field_offset, name) = match self.advance_with_pos() {
+                        (pos, Token::QuotedIdentifier(name)) => (pos, name),
+                        _ => unreachable!("peeked a quoted identifier"),
+                    };
+                    self.advance(); // consume ']'
+                    let rhs = Box::new(Ast::Field { offset: field_offset, name });
+                    Ok(Ast::Subexpr { offset, lhs: left, rhs })
                 } else {
                     self.advance(); // consume token (如 '*' 或 filter)
                     self.parse_wildcard_index(left)
@@ -0,0 +1,55 @@
+// src/condition/condition_params.rs
+
+use serde::Deserialize;
+use crate::node_type::NodeError;
+
+/// If 节点所需的配置参数
+#[derive(Debug, Deserialize)]
+pub struct IfParams {
+    /// 对输入数据求值的 JMESPath 条件，例如 "status == `200`"
+    pub condition: String,
+}
+
+impl IfParams {
+    pub fn validate(&self) -> Result<(), NodeError> {
+        if self.condition.trim().is_empty() {
+            return Err(NodeError::InvalidConfig("Condition cannot be empty".to_owned()));
+        }
+        Ok(())
+    }
+}
+
+/// Switch 节点的一条规则：条件满足时从 `output` 传出数据
+#[derive(Debug, Deserialize)]
+pub struct SwitchRule {
+    pub output: String,
+    pub condition: String,
+}
+
+/// Switch 节点所需的配置参数
+#[derive(Debug, Deserialize)]
+pub struct SwitchParams {
+    /// 按顺序求值的规则
+    pub rules: Vec<SwitchRule>,
+    /// 没有规则匹配时使用的输出；为空时不激活任何输出
+    #[serde(default)]
+    pub fallback_output: Option<String>,
+    /// 为 true 时激活所有匹配的规则，否则只激活第一个匹配的规则
+    #[serde(default)]
+    pub all_matching: bool,
+}
+
+impl SwitchParams {
+    pub fn validate(&self) -> Result<(), NodeError> {
+        if self.rules.is_empty() {
+            return Err(NodeError::InvalidConfig("Switch needs at least one rule".to_owned()));
+        }
+        if let Some(rule) = self.rules.iter().find(|r| r.output.trim().is_empty()) {
+            return Err(NodeError::InvalidConfig(format!(
+                "Rule with condition '{}' has an empty output name",
+                rule.condition
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use crate::node_type::{NodeType, NodeExecutionContext, NodeOutput, NodeError};
+use crate::condition::condition_params::IfParams;
+use crate::condition::{evaluate_condition, IF_NODE_TYPE};
+use async_trait::async_trait;
+
+/// 条件为真时使用的输出名称
+pub const IF_TRUE_OUTPUT: &str = "true";
+/// 条件为假时使用的输出名称
+pub const IF_FALSE_OUTPUT: &str = "false";
+
+/// If 节点：
+/// - 解析 IfParams (condition)
+/// - 对输入数据求值条件
+/// - 原样传出输入数据，条件为真时从 "true" 输出，否则从 "false" 输出
+#[derive(Default)]
+pub struct IfHandler;
+
+impl IfHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for IfHandler {
+    fn name(&self) -> &str {
+        IF_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "If Node"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &NodeExecutionContext
+    ) -> Result<NodeOutput, NodeError> {
+        let params: IfParams = serde_json::from_value(ctx.parameters.clone())
+            .map_err(|e| NodeError::InvalidConfig(format!("Param parse error: {e}")))?;
+        params.validate()?;
+
+        let output = if evaluate_condition(&params.condition, ctx)? {
+            IF_TRUE_OUTPUT
+        } else {
+            IF_FALSE_OUTPUT
+        };
+        Ok(NodeOutput::on_outputs(ctx.input_data.clone(), vec![output.to_string()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::condition::test_support::condition_context;
+    use crate::node_type::build_context;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_if_handler_selects_branch() {
+        let handler = IfHandler::new();
+        let params = json!({ "condition": "status == `200`" });
+
+        let ctx = condition_context(params.clone(), json!({ "status": 200 }));
+        let output = handler.execute(&ctx).await.unwrap();
+        assert_eq!(output.active_outputs(), vec![IF_TRUE_OUTPUT]);
+        assert_eq!(output.data, json!({ "status": 200 }));
+
+        let ctx = condition_context(params, json!({ "status": 502 }));
+        let output = handler.execute(&ctx).await.unwrap();
+        assert_eq!(output.active_outputs(), vec![IF_FALSE_OUTPUT]);
+    }
+
+    #[tokio::test]
+    async fn test_if_handler_invalid_condition() {
+        let handler = IfHandler::new();
+        let ctx = condition_context(json!({ "condition": "a ==" }), json!({}));
+        let err = handler.execute(&ctx).await.unwrap_err();
+        assert!(matches!(err, NodeError::InvalidConfig(_)));
+
+        // 不在工作流运行中（没有求值器）时无法求值条件
+        let ctx = build_context(json!({ "condition": "a" }), json!({}), json!({}), json!({}), None);
+        let err = handler.execute(&ctx).await.unwrap_err();
+        assert!(err.to_string().contains("not enabled"), "{}", err);
+    }
+}
@@ -0,0 +1,72 @@
+// src/condition/mod.rs
+//! mod.rs for the `if` / `switch` nodes ([`IF_NODE_TYPE`] / [`SWITCH_NODE_TYPE`])
+//!
+//! 条件节点对输入数据求值 JMESPath 条件，只从匹配的输出传出数据。
+//! 条件由工作流引擎提供的 [`ExpressionEvaluator`](crate::node_type::ExpressionEvaluator) 求值，可以使用 `$node`、`$globals`、`$env` 等变量。
+
+pub mod condition_params;
+pub mod if_handler;
+pub mod switch_handler;
+
+use crate::node_type::{NodeError, NodeExecutionContext};
+use crate::registry::NodeRegistry;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// If 节点的类型名称
+pub const IF_NODE_TYPE: &str = "af-nodes-base.if";
+/// Switch 节点的类型名称
+pub const SWITCH_NODE_TYPE: &str = "af-nodes-base.switch";
+
+/// 供外部调用以注册 If / Switch 节点到 NodeRegistry
+pub fn register_node(registry: &mut NodeRegistry) {
+    registry.register(Arc::new(if_handler::IfHandler::new()));
+    registry.register(Arc::new(switch_handler::SwitchHandler::new()));
+}
+
+/// 对节点的输入数据求值条件表达式，按 JMESPath 的真值规则返回布尔值：
+/// false、null、空字符串、空数组、空对象为假，其余为真
+pub fn evaluate_condition(condition: &str, ctx: &NodeExecutionContext) -> Result<bool, NodeError> {
+    let evaluator = ctx.expressions.as_ref().ok_or_else(|| {
+        NodeError::InvalidConfig("Expression evaluation is not enabled for this run".to_owned())
+    })?;
+    let result = evaluator
+        .evaluate(condition, &ctx.input_data)
+        .map_err(|e| NodeError::from_kind(e.kind(), format!("Invalid condition: {}", e.message())))?;
+    Ok(is_truthy(&result))
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        Value::Number(_) => true,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use crate::node_type::{build_context, ExpressionEvaluator};
+    use alphaflow_jmes::compile_and_search;
+    use serde_json::json;
+
+    /// 测试用的求值器：只支持对输入数据求值的普通 JMESPath
+    struct PlainJmesPath;
+
+    impl ExpressionEvaluator for PlainJmesPath {
+        fn evaluate(&self, expression: &str, json: &Value) -> Result<Value, NodeError> {
+            compile_and_search(expression, json).map_err(|e| NodeError::InvalidConfig(e.to_string()))
+        }
+    }
+
+    /// 带有求值器的执行上下文
+    pub(crate) fn condition_context(parameters: Value, input: Value) -> NodeExecutionContext {
+        let mut ctx = build_context(parameters, input, json!({}), json!({}), None);
+        ctx.expressions = Some(Arc::new(PlainJmesPath));
+        ctx
+    }
+}
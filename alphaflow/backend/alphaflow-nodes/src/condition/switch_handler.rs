@@ -0,0 +1,92 @@
+use crate::node_type::{NodeType, NodeExecutionContext, NodeOutput, NodeError};
+use crate::condition::condition_params::SwitchParams;
+use crate::condition::{evaluate_condition, SWITCH_NODE_TYPE};
+use async_trait::async_trait;
+
+/// Switch 节点：
+/// - 解析 SwitchParams (rules, fallback_output, all_matching)
+/// - 按顺序对输入数据求值每条规则的条件
+/// - 原样传出输入数据，只激活匹配规则对应的输出；没有匹配时使用 fallback_output
+#[derive(Default)]
+pub struct SwitchHandler;
+
+impl SwitchHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for SwitchHandler {
+    fn name(&self) -> &str {
+        SWITCH_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "Switch Node"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &NodeExecutionContext
+    ) -> Result<NodeOutput, NodeError> {
+        let params: SwitchParams = serde_json::from_value(ctx.parameters.clone())
+            .map_err(|e| NodeError::InvalidConfig(format!("Param parse error: {e}")))?;
+        params.validate()?;
+
+        let mut outputs: Vec<String> = Vec::new();
+        for rule in &params.rules {
+            if !evaluate_condition(&rule.condition, ctx)? {
+                continue;
+            }
+            if !outputs.contains(&rule.output) {
+                outputs.push(rule.output.clone());
+            }
+            if !params.all_matching {
+                break;
+            }
+        }
+        if outputs.is_empty() {
+            outputs.extend(params.fallback_output);
+        }
+        Ok(NodeOutput::on_outputs(ctx.input_data.clone(), outputs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::condition::test_support::condition_context;
+    use serde_json::{json, Value};
+
+    async fn run(params: Value, input: Value) -> Vec<String> {
+        let ctx = condition_context(params, input);
+        SwitchHandler::new().execute(&ctx).await.unwrap().active_outputs()
+    }
+
+    #[tokio::test]
+    async fn test_switch_first_match_and_fallback() {
+        let params = json!({
+            "rules": [
+                { "output": "small", "condition": "n < `10`" },
+                { "output": "even", "condition": "n == `4`" }
+            ],
+            "fallback_output": "other"
+        });
+        assert_eq!(run(params.clone(), json!({ "n": 4 })).await, vec!["small"]);
+        assert_eq!(run(params, json!({ "n": 42 })).await, vec!["other"]);
+    }
+
+    #[tokio::test]
+    async fn test_switch_all_matching_and_no_match() {
+        let params = json!({
+            "rules": [
+                { "output": "small", "condition": "n < `10`" },
+                { "output": "even", "condition": "n == `4`" }
+            ],
+            "all_matching": true
+        });
+        assert_eq!(run(params.clone(), json!({ "n": 4 })).await, vec!["small", "even"]);
+        assert!(run(params, json!({ "n": 42 })).await.is_empty());
+    }
+}
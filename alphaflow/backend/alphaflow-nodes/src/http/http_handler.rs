@@ -62,7 +62,8 @@ impl NodeType for HttpHandler {
 
         // 5) 返回 NodeOutput
         Ok(NodeOutput {
-            data: result_json
+            data: result_json,
+            ..Default::default()
         })
     }
 }
@@ -88,6 +89,7 @@ mod tests {
             globals: json!(null),
             env: json!(null),
             pin_data: None,
+            ..Default::default()
         };
 
         let output = handler.execute(&ctx).await.expect("execution should succeed");
@@ -117,6 +119,7 @@ async fn test_http_handler_missing_url() {
         globals: json!(null),
         env: json!(null),
         pin_data: None,
+        ..Default::default()
     };
 
     let result = handler.execute(&ctx).await;
@@ -8,6 +8,10 @@ use serde_json::Value;
 #[derivative(Hash)]
 pub enum InputMapping {
     Single(String),
+    /// jq 过滤器，写作 `{ "jq": ".items[] | select(.price > 10)" }`
+    Jq {
+        jq: String,
+    },
     Multi {
         fields: BTreeMap<String, String>,
         #[serde(default)]
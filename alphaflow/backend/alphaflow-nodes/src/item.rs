@@ -0,0 +1,136 @@
+// src/item.rs
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 二进制数据（例如文件），内容以 base64 编码保存
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BinaryData {
+    /// base64 编码后的内容
+    pub data: String,
+    pub mime_type: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
+}
+
+/// 输出数据项与输入数据项之间的对应关系
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct PairedItem {
+    /// 对应的输入数据项下标
+    pub item: usize,
+    /// 对应的输入下标（多个父节点时按连接顺序），默认为 0
+    #[serde(default)]
+    pub input: usize,
+}
+
+impl PairedItem {
+    pub fn new(item: usize) -> Self {
+        Self { item, input: 0 }
+    }
+}
+
+/// 节点之间传递的单个数据项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NodeItem {
+    /// 数据项的 JSON 内容
+    pub json: Value,
+    /// 附带的二进制数据：名称 -> 数据
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub binary: HashMap<String, BinaryData>,
+    /// 由哪个输入数据项产生
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paired_item: Option<PairedItem>,
+}
+
+impl NodeItem {
+    pub fn new(json: Value) -> Self {
+        Self {
+            json,
+            ..Default::default()
+        }
+    }
+
+    /// 设置对应的输入数据项
+    pub fn with_paired_item(mut self, paired_item: PairedItem) -> Self {
+        self.paired_item = Some(paired_item);
+        self
+    }
+
+    /// 添加一份二进制数据
+    pub fn with_binary(mut self, name: &str, binary: BinaryData) -> Self {
+        self.binary.insert(name.to_string(), binary);
+        self
+    }
+
+    /// 把 JSON 数据拆分为数据项：数组的每个元素是一项，null 没有数据项，其余值作为单独一项
+    pub fn from_value(value: &Value) -> Vec<NodeItem> {
+        match value {
+            Value::Array(values) => values.iter().cloned().map(NodeItem::new).collect(),
+            Value::Null => Vec::new(),
+            other => vec![NodeItem::new(other.clone())],
+        }
+    }
+
+    /// 把数据项的 JSON 内容合并为一个数组
+    pub fn to_value(items: &[NodeItem]) -> Value {
+        Value::Array(items.iter().map(|item| item.json.clone()).collect())
+    }
+
+    /// 为没有配对信息的输出数据项补全配对：
+    /// 只有一个输入数据项时全部对应到它；输入输出数量相同时按位置一一对应
+    pub fn pair_with_inputs(items: &mut [NodeItem], input_count: usize) {
+        let same_len = items.len() == input_count;
+        for (i, item) in items.iter_mut().enumerate() {
+            if item.paired_item.is_some() {
+                continue;
+            }
+            if input_count == 1 {
+                item.paired_item = Some(PairedItem::new(0));
+            } else if same_len {
+                item.paired_item = Some(PairedItem::new(i));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_items_from_value_and_pairing() {
+        assert!(NodeItem::from_value(&Value::Null).is_empty());
+        assert_eq!(NodeItem::from_value(&json!({ "a": 1 })), vec![NodeItem::new(json!({ "a": 1 }))]);
+
+        let mut items = NodeItem::from_value(&json!([1, 2, 3]));
+        assert_eq!(NodeItem::to_value(&items), json!([1, 2, 3]));
+
+        items[1].paired_item = Some(PairedItem { item: 0, input: 1 });
+        NodeItem::pair_with_inputs(&mut items, 3);
+        let pairs: Vec<_> = items.iter().map(|i| i.paired_item.unwrap()).collect();
+        assert_eq!(pairs, vec![PairedItem::new(0), PairedItem { item: 0, input: 1 }, PairedItem::new(2)]);
+
+        let mut items = NodeItem::from_value(&json!([1, 2]));
+        NodeItem::pair_with_inputs(&mut items, 5);
+        assert!(items.iter().all(|i| i.paired_item.is_none()));
+    }
+
+    #[test]
+    fn test_item_serialization() {
+        let item = NodeItem::new(json!({ "id": 1 }))
+            .with_paired_item(PairedItem::new(2))
+            .with_binary("file", BinaryData {
+                data: "aGVsbG8=".into(),
+                mime_type: "text/plain".into(),
+                file_name: Some("hello.txt".into()),
+            });
+        let value = serde_json::to_value(&item).unwrap();
+        assert_eq!(value["paired_item"], json!({ "item": 2, "input": 0 }));
+        assert_eq!(value["binary"]["file"]["mime_type"], json!("text/plain"));
+
+        let plain = serde_json::to_value(NodeItem::new(json!(1))).unwrap();
+        assert_eq!(plain, json!({ "json": 1 }));
+    }
+}
@@ -3,8 +3,13 @@ mod registry;
 pub mod node_type;
 pub mod node;
 pub mod input_mapping;
+pub mod item;
 pub mod http;
 pub mod openai;
+pub mod condition;
+pub mod merge;
+pub mod split_in_batches;
+pub mod static_data;
 
 pub use registry::*;
 pub use node_type::*;
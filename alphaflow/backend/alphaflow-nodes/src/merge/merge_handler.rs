@@ -0,0 +1,244 @@
+use crate::node_type::{NodeType, NodeExecutionContext, NodeOutput, NodeError};
+use crate::merge::merge_params::{MergeMode, MergeParams};
+use crate::merge::MERGE_NODE_TYPE;
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+/// Merge 节点：
+/// - 解析 MergeParams (mode, key, ...)
+/// - 把每个父节点的输出视为一个输入：数组中的每个元素是一条记录，非数组视为单条记录
+///   （输入按父节点的连接顺序排列，被跳过的父节点对应 null，即空输入，不影响其他输入的位置）
+/// - 按 mode 合并各输入，返回记录数组（pick_first 原样返回选中的输入）
+#[derive(Default)]
+pub struct MergeHandler;
+
+impl MergeHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for MergeHandler {
+    fn name(&self) -> &str {
+        MERGE_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "Merge Node"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &NodeExecutionContext
+    ) -> Result<NodeOutput, NodeError> {
+        let params = MergeParams::parse(&ctx.parameters)?;
+
+        let inputs: Vec<Value> = if ctx.inputs.is_empty() {
+            vec![ctx.input_data.clone()]
+        } else {
+            ctx.inputs.clone()
+        };
+
+        let data = match params.mode {
+            MergeMode::Append => Value::Array(inputs.iter().flat_map(records).collect()),
+            MergeMode::PickFirst => inputs.into_iter().find(|v| !v.is_null()).unwrap_or(Value::Null),
+            MergeMode::MergeByPosition => merge_by_position(&inputs, params.include_unpaired),
+            MergeMode::MergeByKey => merge_by_key(&inputs, params.key.as_deref().unwrap_or_default()),
+            MergeMode::InnerJoin | MergeMode::LeftJoin => {
+                let [left, right] = inputs.as_slice() else {
+                    return Err(NodeError::InvalidConfig(format!(
+                        "Join modes need exactly 2 inputs, got {}",
+                        inputs.len()
+                    )));
+                };
+                join(
+                    left,
+                    right,
+                    params.left_key().unwrap_or_default(),
+                    params.right_key().unwrap_or_default(),
+                    params.mode == MergeMode::LeftJoin,
+                )
+            }
+        };
+
+        Ok(NodeOutput::new(data))
+    }
+}
+
+/// 把一个输入展开为记录列表
+fn records(input: &Value) -> Vec<Value> {
+    match input {
+        Value::Array(items) => items.clone(),
+        Value::Null => Vec::new(),
+        other => vec![other.clone()],
+    }
+}
+
+/// 按 "a.b.c" 形式的路径读取字段
+fn lookup<'a>(record: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(record, |current, segment| current.get(segment))
+        .filter(|v| !v.is_null())
+}
+
+/// 合并两个对象，后者的字段覆盖前者；非对象的一方会被忽略
+fn merge_objects(target: &mut Map<String, Value>, source: &Value) {
+    if let Value::Object(fields) = source {
+        for (k, v) in fields {
+            target.insert(k.clone(), v.clone());
+        }
+    }
+}
+
+fn merge_by_position(inputs: &[Value], include_unpaired: bool) -> Value {
+    let lists: Vec<Vec<Value>> = inputs.iter().map(records).collect();
+    let lengths = lists.iter().map(Vec::len);
+    let len = if include_unpaired { lengths.max() } else { lengths.min() }.unwrap_or(0);
+
+    let merged = (0..len)
+        .map(|i| {
+            let mut obj = Map::new();
+            for list in &lists {
+                if let Some(record) = list.get(i) {
+                    merge_objects(&mut obj, record);
+                }
+            }
+            Value::Object(obj)
+        })
+        .collect();
+    Value::Array(merged)
+}
+
+fn merge_by_key(inputs: &[Value], key: &str) -> Value {
+    // 保持 key 第一次出现的顺序；没有 key 字段的记录原样保留
+    let mut merged: Vec<(Option<Value>, Map<String, Value>)> = Vec::new();
+    for record in inputs.iter().flat_map(records) {
+        let key_value = lookup(&record, key).cloned();
+        let existing = key_value
+            .as_ref()
+            .and_then(|k| merged.iter_mut().find(|(existing, _)| existing.as_ref() == Some(k)));
+        match existing {
+            Some((_, obj)) => merge_objects(obj, &record),
+            None => {
+                let mut obj = Map::new();
+                merge_objects(&mut obj, &record);
+                merged.push((key_value, obj));
+            }
+        }
+    }
+    Value::Array(merged.into_iter().map(|(_, obj)| Value::Object(obj)).collect())
+}
+
+fn join(left: &Value, right: &Value, left_key: &str, right_key: &str, keep_unmatched: bool) -> Value {
+    let right_records = records(right);
+    let mut rows = Vec::new();
+    for l in records(left) {
+        let matches: Vec<&Value> = match lookup(&l, left_key) {
+            Some(k) => right_records
+                .iter()
+                .filter(|r| lookup(r, right_key) == Some(k))
+                .collect(),
+            None => Vec::new(),
+        };
+        if matches.is_empty() && keep_unmatched {
+            rows.push(l.clone());
+        }
+        for r in matches {
+            let mut obj = Map::new();
+            merge_objects(&mut obj, &l);
+            merge_objects(&mut obj, r);
+            rows.push(Value::Object(obj));
+        }
+    }
+    Value::Array(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn merge(params: Value, inputs: Vec<Value>) -> Result<Value, NodeError> {
+        let ctx = NodeExecutionContext {
+            parameters: params,
+            inputs,
+            ..Default::default()
+        };
+        MergeHandler::new().execute(&ctx).await.map(|o| o.data)
+    }
+
+    fn users() -> Value {
+        json!([{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }, { "id": 3, "name": "c" }])
+    }
+
+    fn orders() -> Value {
+        json!([{ "user_id": 1, "total": 10 }, { "user_id": 1, "total": 20 }, { "user_id": 2, "total": 5 }])
+    }
+
+    #[tokio::test]
+    async fn test_append_and_pick_first() {
+        let out = merge(json!({}), vec![json!([1, 2]), json!(3)]).await.unwrap();
+        assert_eq!(out, json!([1, 2, 3]));
+
+        let out = merge(json!({ "mode": "pick_first" }), vec![json!(null), json!({ "x": 1 })])
+            .await
+            .unwrap();
+        assert_eq!(out, json!({ "x": 1 }));
+    }
+
+    #[tokio::test]
+    async fn test_no_config_defaults_to_append() {
+        let out = merge(Value::Null, vec![json!([1]), json!([2, 3])]).await.unwrap();
+        assert_eq!(out, json!([1, 2, 3]));
+    }
+
+    #[tokio::test]
+    async fn test_merge_by_position() {
+        let inputs = vec![json!([{ "a": 1 }, { "a": 2 }]), json!([{ "b": 1 }])];
+        let out = merge(json!({ "mode": "merge_by_position" }), inputs.clone()).await.unwrap();
+        assert_eq!(out, json!([{ "a": 1, "b": 1 }]));
+
+        let params = json!({ "mode": "merge_by_position", "include_unpaired": true });
+        let out = merge(params, inputs).await.unwrap();
+        assert_eq!(out, json!([{ "a": 1, "b": 1 }, { "a": 2 }]));
+    }
+
+    #[tokio::test]
+    async fn test_merge_by_key() {
+        let inputs = vec![
+            json!([{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }]),
+            json!([{ "id": 2, "age": 30 }, { "id": 4, "age": 40 }]),
+        ];
+        let out = merge(json!({ "mode": "merge_by_key", "key": "id" }), inputs).await.unwrap();
+        assert_eq!(
+            out,
+            json!([{ "id": 1, "name": "a" }, { "id": 2, "name": "b", "age": 30 }, { "id": 4, "age": 40 }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inner_and_left_join() {
+        let params = json!({ "mode": "inner_join", "left_key": "id", "right_key": "user_id" });
+        let out = merge(params, vec![users(), orders()]).await.unwrap();
+        let totals: Vec<_> = out.as_array().unwrap().iter().map(|r| (r["name"].clone(), r["total"].clone())).collect();
+        assert_eq!(
+            totals,
+            vec![(json!("a"), json!(10)), (json!("a"), json!(20)), (json!("b"), json!(5))]
+        );
+
+        let params = json!({ "mode": "left_join", "left_key": "id", "right_key": "user_id" });
+        let out = merge(params, vec![users(), orders()]).await.unwrap();
+        assert_eq!(out.as_array().unwrap().len(), 4);
+        assert_eq!(out[3], json!({ "id": 3, "name": "c" }));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        let err = merge(json!({ "mode": "merge_by_key" }), vec![json!([])]).await.unwrap_err();
+        assert!(matches!(err, NodeError::InvalidConfig(_)));
+
+        let err = merge(json!({ "mode": "inner_join", "key": "id" }), vec![users()]).await.unwrap_err();
+        assert!(matches!(err, NodeError::InvalidConfig(_)));
+    }
+}
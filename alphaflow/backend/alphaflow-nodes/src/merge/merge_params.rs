@@ -0,0 +1,85 @@
+// src/merge/merge_params.rs
+
+use serde::Deserialize;
+use serde_json::Value;
+use crate::node_type::NodeError;
+
+/// 合并方式
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// 依次拼接所有输入的记录（默认）
+    #[default]
+    Append,
+    /// 按 key 字段合并所有输入中 key 相同的记录，key 不同的记录各自保留
+    MergeByKey,
+    /// 按位置合并：第 i 条记录与其他输入的第 i 条记录合并为一个对象
+    MergeByPosition,
+    /// 使用第一个非空的输入
+    PickFirst,
+    /// 两个输入按 key 做内连接，只保留匹配的记录
+    InnerJoin,
+    /// 两个输入按 key 做左连接，保留第一个输入的全部记录
+    LeftJoin,
+}
+
+/// Merge 节点所需的配置参数
+#[derive(Debug, Default, Deserialize)]
+pub struct MergeParams {
+    #[serde(default)]
+    pub mode: MergeMode,
+
+    /// 用于 merge_by_key / inner_join / left_join 的 key 字段，支持 "a.b" 形式的嵌套路径
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// 连接时第一个输入使用的 key 字段，缺省时使用 `key`
+    #[serde(default)]
+    pub left_key: Option<String>,
+
+    /// 连接时第二个输入使用的 key 字段，缺省时使用 `key`
+    #[serde(default)]
+    pub right_key: Option<String>,
+
+    /// merge_by_position 时是否保留没有配对的记录，默认只合并到最短输入的长度
+    #[serde(default)]
+    pub include_unpaired: bool,
+}
+
+impl MergeParams {
+    /// 解析并校验节点参数，参数为空时使用默认值（append）
+    pub fn parse(parameters: &Value) -> Result<Self, NodeError> {
+        let params: Self = match parameters {
+            Value::Null => Self::default(),
+            other => serde_json::from_value(other.clone())
+                .map_err(|e| NodeError::InvalidConfig(format!("Param parse error: {e}")))?,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// 校验各模式所需的字段
+    pub fn validate(&self) -> Result<(), NodeError> {
+        match self.mode {
+            MergeMode::MergeByKey if self.key.is_none() => Err(NodeError::InvalidConfig(
+                "Mode 'merge_by_key' requires 'key'".to_owned(),
+            )),
+            MergeMode::InnerJoin | MergeMode::LeftJoin
+                if self.left_key().is_none() || self.right_key().is_none() =>
+            {
+                Err(NodeError::InvalidConfig(
+                    "Join modes require 'key' or both 'left_key' and 'right_key'".to_owned(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn left_key(&self) -> Option<&str> {
+        self.left_key.as_deref().or(self.key.as_deref())
+    }
+
+    pub fn right_key(&self) -> Option<&str> {
+        self.right_key.as_deref().or(self.key.as_deref())
+    }
+}
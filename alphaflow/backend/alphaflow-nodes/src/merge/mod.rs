@@ -0,0 +1,18 @@
+// src/merge/mod.rs
+//! mod.rs for the `merge` node
+//!
+//! Provides `register_node` which registers MergeHandler into NodeRegistry.
+
+pub mod merge_params;
+pub mod merge_handler;
+
+use crate::registry::NodeRegistry;
+use std::sync::Arc;
+
+/// Merge 节点的类型名称
+pub const MERGE_NODE_TYPE: &str = "af-nodes-base.merge";
+
+/// 供外部调用以注册 Merge 节点到 NodeRegistry
+pub fn register_node(registry: &mut NodeRegistry) {
+    registry.register(Arc::new(merge_handler::MergeHandler::new()));
+}
@@ -1,9 +1,166 @@
+use std::time::Duration;
 use derivative::Derivative;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use crate::input_mapping::InputMapping;
+use crate::node_type::{NodeError, NodeErrorKind};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Derivative)]
+/// 多个父节点汇聚到同一节点时的等待策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinMode {
+    /// 等待所有父节点结束后再执行（默认）
+    #[default]
+    WaitAll,
+    /// 任意一个父节点产生输出后立即执行
+    WaitAny,
+    /// 收到 N 个父节点的输出后执行
+    WaitN(usize),
+}
+
+/// 节点对输入数据项的执行方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemExecutionMode {
+    /// 对全部数据项执行一次（默认）
+    #[default]
+    RunOnceForAllItems,
+    /// 对每个数据项分别执行一次，输出按输入顺序拼接
+    RunOnceForEachItem,
+}
+
+/// 节点执行失败（包括重试用尽）后的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OnError {
+    /// 停止整个工作流（默认）
+    #[default]
+    StopWorkflow,
+    /// 以错误数据作为正常输出继续执行下游节点
+    ContinueRegularOutput,
+    /// 以错误数据作为 "error" 输出继续执行，只有连接到该输出的节点会收到数据
+    ContinueErrorOutput,
+}
+
+/// 两次重试之间的等待策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Backoff {
+    /// 每次重试前等待固定时长
+    Fixed { delay_ms: u64 },
+    /// 等待时长按 `factor` 倍数递增：initial_delay_ms * factor^(n-1)，不超过 max_delay_ms
+    Exponential {
+        initial_delay_ms: u64,
+        #[serde(default = "default_backoff_factor")]
+        factor: u32,
+        max_delay_ms: u64,
+    },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::Fixed { delay_ms: 1000 }
+    }
+}
+
+fn default_backoff_factor() -> u32 {
+    2
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_on() -> Vec<NodeErrorKind> {
+    vec![NodeErrorKind::ExecutionFailed]
+}
+
+/// 节点重试策略
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// 最多执行次数（包含第一次执行），1 表示不重试
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// 重试前的等待策略
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// 在等待时长基础上随机增加 0..=jitter_ms 毫秒，避免多个节点同时重试
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// 只有这些类别的错误才会重试，默认只重试 ExecutionFailed
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<NodeErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff: Backoff::default(),
+            jitter_ms: 0,
+            retry_on: default_retry_on(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 创建一个最多执行 `max_attempts` 次的重试策略，其余字段使用默认值
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// 设置等待策略
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 设置随机抖动上限
+    pub fn with_jitter_ms(mut self, jitter_ms: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self
+    }
+
+    /// 设置需要重试的错误类别
+    pub fn with_retry_on(mut self, kinds: Vec<NodeErrorKind>) -> Self {
+        self.retry_on = kinds;
+        self
+    }
+
+    /// 第 `attempt` 次执行（从 1 开始）失败后，是否应当再次执行
+    pub fn should_retry(&self, attempt: u32, err: &NodeError) -> bool {
+        attempt < self.max_attempts && self.retry_on.contains(&err.kind())
+    }
+
+    /// 第 `attempt` 次执行（从 1 开始）失败后、下一次执行前的等待时长，不含抖动
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let ms = match self.backoff {
+            Backoff::Fixed { delay_ms } => delay_ms,
+            Backoff::Exponential { initial_delay_ms, factor, max_delay_ms } => {
+                let exp = attempt.saturating_sub(1);
+                let multiplier = (factor as u64).checked_pow(exp).unwrap_or(u64::MAX);
+                initial_delay_ms.saturating_mul(multiplier).min(max_delay_ms)
+            }
+        };
+        Duration::from_millis(ms)
+    }
+
+    /// 包含随机抖动的等待时长
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let jitter = if self.jitter_ms > 0 {
+            rand::thread_rng().gen_range(0..=self.jitter_ms)
+        } else {
+            0
+        };
+        self.base_delay(attempt) + Duration::from_millis(jitter)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Derivative)]
 #[derivative(Hash)]
 pub struct Node {
     /// 工作流中此节点的唯一标识，例如 "chat_node"
@@ -31,6 +188,24 @@ pub struct Node {
     #[serde(default)]
     #[derivative(Hash = "ignore")]
     pub custom_config: Option<Value>,
+    /// 多个父节点时的等待策略，默认等待全部父节点
+    #[serde(default)]
+    pub join_mode: JoinMode,
+    /// 失败时的重试策略，为空时不重试
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    /// 执行失败后的处理方式，默认停止工作流
+    #[serde(default)]
+    pub on_error: OnError,
+    /// 对输入数据项的执行方式，默认对全部数据项执行一次
+    #[serde(default)]
+    pub execution_mode: ItemExecutionMode,
+    /// 单次执行的超时时间（毫秒），为空时不限制
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// 节点在编辑器画布上的位置 [x, y]（可选）
+    #[serde(default)]
+    pub position: Option<[i64; 2]>,
 }
 
 impl Node {
@@ -45,6 +220,12 @@ impl Node {
             display_name: None,
             description: None,
             custom_config: None,
+            join_mode: JoinMode::WaitAll,
+            retry_policy: None,
+            on_error: OnError::StopWorkflow,
+            execution_mode: ItemExecutionMode::RunOnceForAllItems,
+            timeout_ms: None,
+            position: None,
         }
     }
 
@@ -83,6 +264,42 @@ impl Node {
         self.custom_config = Some(config);
         self
     }
+
+    /// 设置多父节点的等待策略
+    pub fn with_join_mode(mut self, join_mode: JoinMode) -> Self {
+        self.join_mode = join_mode;
+        self
+    }
+
+    /// 设置失败重试策略
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// 设置执行失败后的处理方式
+    pub fn with_on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// 设置对输入数据项的执行方式
+    pub fn with_execution_mode(mut self, mode: ItemExecutionMode) -> Self {
+        self.execution_mode = mode;
+        self
+    }
+
+    /// 设置单次执行的超时时间（毫秒）
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// 设置节点在画布上的位置
+    pub fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = Some([x, y]);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -110,5 +327,52 @@ mod tests {
         let deserialized: Node = serde_json::from_str(&serialized).unwrap();
         assert_eq!(deserialized.name, "test_node");
         assert_eq!(deserialized.node_type_name, "openai");
+        assert_eq!(deserialized.join_mode, JoinMode::WaitAll);
+        assert_eq!(deserialized.on_error, OnError::StopWorkflow);
+    }
+
+    #[test]
+    fn test_join_mode_serialization() {
+        let node = Node::new("join", "http").with_join_mode(JoinMode::WaitN(2));
+        let value = serde_json::to_value(&node).unwrap();
+        assert_eq!(value["join_mode"], json!({ "wait_n": 2 }));
+
+        let parsed: Node = serde_json::from_value(json!({
+            "name": "join",
+            "node_type_name": "http",
+            "join_mode": "wait_any"
+        }))
+        .unwrap();
+        assert_eq!(parsed.join_mode, JoinMode::WaitAny);
+
+        let node = Node::new("code", "code").with_execution_mode(ItemExecutionMode::RunOnceForEachItem);
+        let value = serde_json::to_value(&node).unwrap();
+        assert_eq!(value["execution_mode"], json!("runOnceForEachItem"));
+    }
+
+    #[test]
+    fn test_retry_policy_backoff_and_serialization() {
+        let policy: RetryPolicy = serde_json::from_value(json!({
+            "max_attempts": 4,
+            "backoff": { "type": "exponential", "initial_delay_ms": 100, "max_delay_ms": 300 },
+            "jitter_ms": 50
+        }))
+        .unwrap();
+        assert_eq!(policy.retry_on, vec![NodeErrorKind::ExecutionFailed]);
+        assert_eq!(policy.base_delay(1), Duration::from_millis(100));
+        assert_eq!(policy.base_delay(2), Duration::from_millis(200));
+        assert_eq!(policy.base_delay(3), Duration::from_millis(300));
+        let delay = policy.delay_for_attempt(1);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(150));
+
+        let failed = NodeError::ExecutionFailed("502".into());
+        let invalid = NodeError::InvalidConfig("bad url".into());
+        assert!(policy.should_retry(3, &failed));
+        assert!(!policy.should_retry(4, &failed));
+        assert!(!policy.should_retry(1, &invalid));
+
+        let node = Node::new("http", "http").with_retry_policy(RetryPolicy::new(2));
+        let value = serde_json::to_value(&node).unwrap();
+        assert_eq!(value["retry_policy"]["backoff"], json!({ "type": "fixed", "delay_ms": 1000 }));
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,16 @@
 // src/node_type.rs
 
+use std::fmt;
+use std::sync::Arc;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
+use crate::item::NodeItem;
+use crate::static_data::NodeStaticData;
 
 /// 节点执行时的上下文信息，用于传递各类运行时数据。
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct NodeExecutionContext {
     /// 节点的参数，由前端配置传入，例如映射表达式、API 密钥等。
     pub parameters: Value,
@@ -17,12 +22,99 @@ pub struct NodeExecutionContext {
     pub env: Value,
     /// 如果节点被 Pin，则存放该节点固定使用的数据。
     pub pin_data: Option<Value>,
+    /// 按连接顺序排列的各父节点输出（未合并），供 Merge 等需要区分输入来源的节点使用。
+    /// 每个父节点占一项，没有输出的父节点（例如未激活的分支）为 `null`；
+    /// 为空时表示只有 `input_data` 一个输入。
+    pub inputs: Vec<Value>,
+    /// 输入数据项列表（与 `input_data` 对应，另外带有二进制数据和配对信息）。
+    pub items: Vec<NodeItem>,
+    /// 执行其他工作流的能力，由工作流引擎提供；为空时无法执行子工作流。
+    pub workflows: Option<Arc<dyn WorkflowRunner>>,
+    /// 求值表达式（If / Switch 的条件等）的能力，由工作流引擎提供；为空时无法求值表达式。
+    pub expressions: Option<Arc<dyn ExpressionEvaluator>>,
+    /// 跨运行保留的静态数据（本节点及工作流级别），由工作流引擎提供并在运行结束后保存；
+    /// 为空时表示不在工作流运行中。
+    pub static_data: Option<NodeStaticData>,
+}
+
+/// 供节点执行其他工作流（子工作流）的接口，由工作流引擎实现。
+#[async_trait]
+pub trait WorkflowRunner: Send + Sync {
+    /// 按节点参数加载并执行另一个工作流，`items` 交给其触发节点，返回子工作流的最终输出。
+    async fn run_workflow(&self, parameters: &Value, items: Vec<NodeItem>) -> Result<NodeOutput, NodeError>;
+}
+
+impl fmt::Debug for dyn WorkflowRunner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WorkflowRunner")
+    }
+}
+
+/// 供节点求值 JMESPath 表达式的接口，由工作流引擎实现；
+/// 表达式可以使用 `$json`、`$node`、`$globals`、`$env`、`$now` 变量。
+pub trait ExpressionEvaluator: Send + Sync {
+    /// 以 `json` 作为当前数据（`@` 和 `$json`）求值表达式，错误信息带有出错的行号和列号。
+    fn evaluate(&self, expression: &str, json: &Value) -> Result<Value, NodeError>;
+}
+
+impl fmt::Debug for dyn ExpressionEvaluator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExpressionEvaluator")
+    }
 }
 
+/// 节点默认输出的名称，未指定输出的连接都使用该输出。
+pub const MAIN_OUTPUT: &str = "main";
+/// 错误输出的名称，节点以 `continue_error_output` 方式处理错误时，错误数据从该输出传出。
+pub const ERROR_OUTPUT: &str = "error";
+
 /// 节点执行返回值，统一以 JSON 格式返回，便于后续节点解析。
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct NodeOutput {
     pub data: Value,
+    /// 数据从哪些输出传出：None 表示默认输出 [`MAIN_OUTPUT`]；
+    /// 条件类节点通过它只激活匹配的分支，`Some(vec![])` 表示不激活任何输出。
+    pub outputs: Option<Vec<String>>,
+    /// 输出数据项；为空时由 `data` 拆分得到（见 [`NodeItem::from_value`]）。
+    /// 需要传出二进制数据或指定配对信息的节点应当设置该字段。
+    pub items: Option<Vec<NodeItem>>,
+}
+
+impl NodeOutput {
+    /// 从默认输出传出数据
+    pub fn new(data: Value) -> Self {
+        Self { data, ..Default::default() }
+    }
+
+    /// 只从指定的输出传出数据
+    pub fn on_outputs(data: Value, outputs: Vec<String>) -> Self {
+        Self { data, outputs: Some(outputs), ..Default::default() }
+    }
+
+    /// 以数据项列表作为输出，`data` 为各数据项 JSON 组成的数组
+    pub fn from_items(items: Vec<NodeItem>) -> Self {
+        Self {
+            data: NodeItem::to_value(&items),
+            items: Some(items),
+            ..Default::default()
+        }
+    }
+
+    /// 输出的数据项列表
+    pub fn output_items(&self) -> Vec<NodeItem> {
+        match &self.items {
+            Some(items) => items.clone(),
+            None => NodeItem::from_value(&self.data),
+        }
+    }
+
+    /// 实际激活的输出名称
+    pub fn active_outputs(&self) -> Vec<String> {
+        match &self.outputs {
+            Some(outputs) => outputs.clone(),
+            None => vec![MAIN_OUTPUT.to_string()],
+        }
+    }
 }
 
 /// 节点执行过程中可能出现的错误类型。
@@ -32,6 +124,52 @@ pub enum NodeError {
     InvalidConfig(String),
     #[error("Execution failed: {0}")]
     ExecutionFailed(String),
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+}
+
+/// 错误类别，用于重试策略等按类别匹配错误的场景。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeErrorKind {
+    InvalidConfig,
+    ExecutionFailed,
+    Timeout,
+    Cancelled,
+}
+
+impl NodeError {
+    /// 返回错误类别。
+    pub fn kind(&self) -> NodeErrorKind {
+        match self {
+            NodeError::InvalidConfig(_) => NodeErrorKind::InvalidConfig,
+            NodeError::ExecutionFailed(_) => NodeErrorKind::ExecutionFailed,
+            NodeError::Timeout(_) => NodeErrorKind::Timeout,
+            NodeError::Cancelled(_) => NodeErrorKind::Cancelled,
+        }
+    }
+
+    /// 返回不带类别前缀的错误信息。
+    pub fn message(&self) -> &str {
+        match self {
+            NodeError::InvalidConfig(msg)
+            | NodeError::ExecutionFailed(msg)
+            | NodeError::Timeout(msg)
+            | NodeError::Cancelled(msg) => msg,
+        }
+    }
+
+    /// 根据类别和错误信息构造错误，与 [`NodeError::kind`]、[`NodeError::message`] 互逆。
+    pub fn from_kind(kind: NodeErrorKind, message: impl Into<String>) -> Self {
+        match kind {
+            NodeErrorKind::InvalidConfig => NodeError::InvalidConfig(message.into()),
+            NodeErrorKind::ExecutionFailed => NodeError::ExecutionFailed(message.into()),
+            NodeErrorKind::Timeout => NodeError::Timeout(message.into()),
+            NodeErrorKind::Cancelled => NodeError::Cancelled(message.into()),
+        }
+    }
 }
 
 /// 节点元数据，用于动态生成前端配置界面或进行节点描述。
@@ -86,10 +224,15 @@ pub fn build_context(
 ) -> NodeExecutionContext {
     NodeExecutionContext {
         parameters,
+        items: NodeItem::from_value(&input_data),
         input_data,
         globals,
         env,
         pin_data,
+        inputs: Vec::new(),
+        workflows: None,
+        expressions: None,
+        static_data: None,
     }
 }
 
@@ -119,7 +262,7 @@ mod tests {
                 "parameters": ctx.parameters,
                 "input_data": ctx.input_data
             });
-            Ok(NodeOutput { data: out })
+            Ok(NodeOutput { data: out, ..Default::default() })
         }
     }
 
@@ -93,7 +93,8 @@ impl NodeType for OpenAiChatHandler {
         });
 
         Ok(NodeOutput {
-            data: result_json
+            data: result_json,
+            ..Default::default()
         })
     }
 }
@@ -134,6 +135,7 @@ mod tests {
             globals: json!(null),
             env: json!(null),
             pin_data: None,
+            ..Default::default()
         };
 
         let result = handler.execute(&ctx).await;
@@ -164,6 +166,7 @@ mod tests {
             globals: json!(null),
             env: json!(null),
             pin_data: None,
+            ..Default::default()
         };
 
         let result = handler.execute(&ctx).await;
@@ -58,7 +58,8 @@ mod tests {
             _: &NodeExecutionContext
         ) -> Result<NodeOutput, NodeError> {
             Ok(NodeOutput {
-                data: Value::String("mock output".into())
+                data: Value::String("mock output".into()),
+                ..Default::default()
             })
         }
     }
@@ -80,6 +81,7 @@ mod tests {
             globals: Value::Null,
             env: Value::Null,
             pin_data: None,
+            ..Default::default()
         }).await;
         assert!(result.is_ok());
         assert_eq!(
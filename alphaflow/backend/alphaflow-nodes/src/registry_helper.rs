@@ -3,11 +3,19 @@
 use crate::registry::NodeRegistry;
 use crate::http::http_handler::HttpHandler;
 use crate::openai::openai_handler::OpenAiChatHandler;
+use crate::condition::if_handler::IfHandler;
+use crate::condition::switch_handler::SwitchHandler;
+use crate::merge::merge_handler::MergeHandler;
+use crate::split_in_batches::split_in_batches_handler::SplitInBatchesHandler;
 use std::sync::Arc;
 
-/// 一次性注册所有节点 (HTTP, OpenAI 等)，以简化用户调用。
+/// 一次性注册所有节点 (HTTP, OpenAI, If, Switch, Merge, Split In Batches 等)，以简化用户调用。
 pub fn register_all_nodes(registry: &mut NodeRegistry) {
     // 如果你还有更多节点，也在此依次 register
     registry.register(Arc::new(HttpHandler::new()));
     registry.register(Arc::new(OpenAiChatHandler::new()));
+    registry.register(Arc::new(IfHandler::new()));
+    registry.register(Arc::new(SwitchHandler::new()));
+    registry.register(Arc::new(MergeHandler::new()));
+    registry.register(Arc::new(SplitInBatchesHandler::new()));
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+// src/split_in_batches/mod.rs
+//! mod.rs for the `split_in_batches` node
+//!
+//! Provides `register_node` which registers SplitInBatchesHandler into NodeRegistry.
+
+pub mod split_in_batches_params;
+pub mod split_in_batches_handler;
+
+use crate::registry::NodeRegistry;
+use std::sync::Arc;
+
+/// Split In Batches（循环）节点的类型名称
+pub const SPLIT_IN_BATCHES_NODE_TYPE: &str = "af-nodes-base.splitInBatches";
+/// 每个批次从该输出传给循环体
+pub const LOOP_OUTPUT: &str = "loop";
+/// 所有批次都经过循环体后，汇总结果从该输出传出
+pub const DONE_OUTPUT: &str = "done";
+
+/// 供外部调用以注册 Split In Batches 节点到 NodeRegistry
+pub fn register_node(registry: &mut NodeRegistry) {
+    registry.register(Arc::new(split_in_batches_handler::SplitInBatchesHandler::new()));
+}
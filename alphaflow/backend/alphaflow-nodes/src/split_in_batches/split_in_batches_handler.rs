@@ -0,0 +1,40 @@
+use crate::node_type::{NodeType, NodeExecutionContext, NodeOutput, NodeError};
+use crate::split_in_batches::split_in_batches_params::SplitInBatchesParams;
+use crate::split_in_batches::{DONE_OUTPUT, SPLIT_IN_BATCHES_NODE_TYPE};
+use async_trait::async_trait;
+
+/// Split In Batches（循环）节点：
+/// - 把输入数据项按 batch_size 切分为批次，依次从 "loop" 输出传给循环体
+/// - 循环体的最后一个节点连回本节点，全部批次处理完后从 "done" 输出汇总结果
+///
+/// 批次的迭代由工作流执行器完成（循环状态保存在执行器中）；
+/// 在不支持循环的环境中直接执行时，只校验参数并把全部输入数据项从 "done" 输出传出。
+#[derive(Default)]
+pub struct SplitInBatchesHandler;
+
+impl SplitInBatchesHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for SplitInBatchesHandler {
+    fn name(&self) -> &str {
+        SPLIT_IN_BATCHES_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "Split In Batches Node"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &NodeExecutionContext
+    ) -> Result<NodeOutput, NodeError> {
+        SplitInBatchesParams::parse(&ctx.parameters)?;
+        let mut output = NodeOutput::from_items(ctx.items.clone());
+        output.outputs = Some(vec![DONE_OUTPUT.to_string()]);
+        Ok(output)
+    }
+}
@@ -0,0 +1,64 @@
+// src/split_in_batches/split_in_batches_params.rs
+
+use serde::Deserialize;
+use serde_json::Value;
+use crate::item::NodeItem;
+use crate::node_type::NodeError;
+
+/// 默认的批次大小
+pub const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// Split In Batches 节点所需的配置参数
+#[derive(Debug, Deserialize)]
+pub struct SplitInBatchesParams {
+    /// 每个批次包含的数据项数量，必须大于 0
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_BATCH_SIZE
+}
+
+impl SplitInBatchesParams {
+    /// 解析并校验节点参数，参数为空时使用默认值
+    pub fn parse(parameters: &Value) -> Result<Self, NodeError> {
+        let params: Self = match parameters {
+            Value::Null => Self { batch_size: DEFAULT_BATCH_SIZE },
+            other => serde_json::from_value(other.clone())
+                .map_err(|e| NodeError::InvalidConfig(format!("Param parse error: {e}")))?,
+        };
+        if params.batch_size == 0 {
+            return Err(NodeError::InvalidConfig("'batch_size' must be greater than 0".to_owned()));
+        }
+        Ok(params)
+    }
+
+    /// 按批次大小切分数据项，最后一个批次可能不满
+    pub fn batches(&self, items: Vec<NodeItem>) -> Vec<Vec<NodeItem>> {
+        let mut batches = Vec::new();
+        let mut items = items.into_iter().peekable();
+        while items.peek().is_some() {
+            batches.push(items.by_ref().take(self.batch_size).collect());
+        }
+        batches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_batches() {
+        let params = SplitInBatchesParams::parse(&json!({ "batch_size": 2 })).unwrap();
+        let items = NodeItem::from_value(&json!([1, 2, 3, 4, 5]));
+        let sizes: Vec<usize> = params.batches(items).iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert!(params.batches(Vec::new()).is_empty());
+
+        assert_eq!(SplitInBatchesParams::parse(&Value::Null).unwrap().batch_size, DEFAULT_BATCH_SIZE);
+        assert!(SplitInBatchesParams::parse(&json!({ "batch_size": 0 })).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// src/static_data.rs
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 工作流静态数据：跨运行保留的键值存储，例如轮询类节点记住的 "last seen id" 或游标
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StaticData {
+    /// 工作流级别的数据，所有节点共享
+    pub global: Map<String, Value>,
+    /// 节点 ID -> 该节点自己的数据
+    pub nodes: BTreeMap<String, Map<String, Value>>,
+}
+
+impl StaticData {
+    /// 没有任何数据
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.nodes.values().all(Map::is_empty)
+    }
+}
+
+#[derive(Debug, Default)]
+struct StoreState {
+    data: StaticData,
+    changed: bool,
+}
+
+/// 一次运行中共享的静态数据，由工作流引擎创建，运行结束后按需写回
+#[derive(Debug, Clone, Default)]
+pub struct StaticDataStore {
+    state: Arc<Mutex<StoreState>>,
+}
+
+impl StaticDataStore {
+    pub fn new(data: StaticData) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(StoreState { data, changed: false })),
+        }
+    }
+
+    /// 指定节点可以访问的视图
+    pub fn for_node(&self, node_id: &str) -> NodeStaticData {
+        NodeStaticData {
+            store: self.clone(),
+            node_id: node_id.to_string(),
+        }
+    }
+
+    /// 当前数据的副本
+    pub fn snapshot(&self) -> StaticData {
+        self.lock().data.clone()
+    }
+
+    /// 创建后是否被修改过
+    pub fn is_changed(&self) -> bool {
+        self.lock().changed
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StoreState> {
+        // 持有锁期间不会 panic，忽略中毒状态
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 节点执行时看到的静态数据：读写自己的节点级数据，以及工作流级数据
+#[derive(Debug, Clone)]
+pub struct NodeStaticData {
+    store: StaticDataStore,
+    node_id: String,
+}
+
+impl NodeStaticData {
+    /// 读取本节点的数据
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let state = self.store.lock();
+        state.data.nodes.get(&self.node_id).and_then(|data| data.get(key)).cloned()
+    }
+
+    /// 写入本节点的数据
+    pub fn set(&self, key: &str, value: Value) {
+        let mut state = self.store.lock();
+        state.data.nodes.entry(self.node_id.clone()).or_default().insert(key.to_string(), value);
+        state.changed = true;
+    }
+
+    /// 删除本节点的数据，返回原有的值
+    pub fn remove(&self, key: &str) -> Option<Value> {
+        let mut state = self.store.lock();
+        let removed = state.data.nodes.get_mut(&self.node_id).and_then(|data| data.remove(key));
+        state.changed |= removed.is_some();
+        removed
+    }
+
+    /// 读取工作流级别的数据
+    pub fn get_global(&self, key: &str) -> Option<Value> {
+        self.store.lock().data.global.get(key).cloned()
+    }
+
+    /// 写入工作流级别的数据
+    pub fn set_global(&self, key: &str, value: Value) {
+        let mut state = self.store.lock();
+        state.data.global.insert(key.to_string(), value);
+        state.changed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_node_views_share_one_store() {
+        let store = StaticDataStore::new(StaticData::default());
+        let poll = store.for_node("poll");
+        let other = store.for_node("other");
+        assert!(!store.is_changed());
+
+        poll.set("cursor", json!(42));
+        poll.set_global("last_run", json!("2025-03-01"));
+        assert_eq!(poll.get("cursor"), Some(json!(42)));
+        assert_eq!(other.get("cursor"), None);
+        assert_eq!(other.get_global("last_run"), Some(json!("2025-03-01")));
+        assert!(store.is_changed());
+
+        let snapshot = store.snapshot();
+        assert_eq!(
+            serde_json::to_value(&snapshot).unwrap(),
+            json!({ "global": { "last_run": "2025-03-01" }, "nodes": { "poll": { "cursor": 42 } } })
+        );
+        assert_eq!(poll.remove("cursor"), Some(json!(42)));
+        assert!(store.snapshot().nodes["poll"].is_empty());
+    }
+}
@@ -0,0 +1,4 @@
+-- add_executions_wait_till/down.sql
+
+DROP INDEX IF EXISTS "idx_executions_wait_till";
+ALTER TABLE "executions" DROP COLUMN "wait_till";
@@ -0,0 +1,5 @@
+-- add_executions_wait_till/up.sql
+
+-- 在 Wait 节点处暂停的执行：到达该时间后恢复运行（无限等待使用 3000 年）
+ALTER TABLE "executions" ADD COLUMN "wait_till" TIMESTAMP;
+CREATE INDEX IF NOT EXISTS "idx_executions_wait_till" ON "executions" ("wait_till");
@@ -1,5 +1,6 @@
 // src/db/execution_ops.rs
 
+use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
 use diesel::result::QueryResult;
 use crate::models::execution::{Execution, NewExecution};
@@ -38,4 +39,93 @@ pub fn update_execution_mode(conn: &mut SqliteConnection, exec_id: &str, new_mod
 pub fn delete_execution(conn: &mut SqliteConnection, exec_id: &str) -> QueryResult<usize> {
     diesel::delete(executions::table.filter(executions::id.eq(Some(exec_id.to_string()))))
         .execute(conn)
-}
\ No newline at end of file
+}
+
+pub fn update_execution_data(conn: &mut SqliteConnection, exec_id: &str, data: &str) -> QueryResult<usize> {
+    // 运行过程中保存最新的执行数据
+    diesel::update(executions::table.filter(executions::id.eq(Some(exec_id.to_string()))))
+        .set((
+            executions::data.eq(Some(data)),
+            executions::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+pub fn finish_execution(
+    conn: &mut SqliteConnection,
+    exec_id: &str,
+    finished: bool,
+    stopped_at: NaiveDateTime,
+    data: &str,
+) -> QueryResult<usize> {
+    // 运行结束: finished 表示是否成功完成
+    diesel::update(executions::table.filter(executions::id.eq(Some(exec_id.to_string()))))
+        .set((
+            executions::finished.eq(finished),
+            executions::stopped_at.eq(Some(stopped_at)),
+            executions::wait_till.eq(None::<NaiveDateTime>),
+            executions::data.eq(Some(data)),
+            executions::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+pub fn set_execution_waiting(
+    conn: &mut SqliteConnection,
+    exec_id: &str,
+    wait_till: NaiveDateTime,
+    data: &str,
+) -> QueryResult<usize> {
+    // 运行在 Wait 节点处暂停: 记录恢复时间, finished 保持 false
+    diesel::update(executions::table.filter(executions::id.eq(Some(exec_id.to_string()))))
+        .set((
+            executions::wait_till.eq(Some(wait_till)),
+            executions::data.eq(Some(data)),
+            executions::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+pub fn mark_interrupted_executions(
+    conn: &mut SqliteConnection,
+    stale_before: NaiveDateTime,
+    stopped_at: NaiveDateTime,
+) -> QueryResult<usize> {
+    // 仍标记为运行中(未结束、没有 stopped_at、不在等待)且 stale_before 之后没有更新过的执行: 设置 stopped_at 表示已中断
+    diesel::update(
+        executions::table
+            .filter(executions::finished.eq(false))
+            .filter(executions::stopped_at.is_null())
+            .filter(executions::wait_till.is_null())
+            .filter(executions::updated_at.lt(stale_before)),
+    )
+    .set((
+        executions::stopped_at.eq(Some(stopped_at)),
+        executions::updated_at.eq(Utc::now().naive_utc()),
+    ))
+    .execute(conn)
+}
+
+pub fn list_due_executions(conn: &mut SqliteConnection, now: NaiveDateTime) -> QueryResult<Vec<Execution>> {
+    // wait_till 已到期、尚未结束的执行
+    executions::table
+        .filter(executions::finished.eq(false))
+        .filter(executions::wait_till.le(now))
+        .order(executions::wait_till.asc())
+        .load(conn)
+}
+
+pub fn claim_waiting_execution(
+    conn: &mut SqliteConnection,
+    exec_id: &str,
+    wait_till: NaiveDateTime,
+) -> QueryResult<usize> {
+    // 清空 wait_till 表示已交给恢复任务; wait_till 已被修改(例如已被其他调度方领取)时返回 0
+    diesel::update(
+        executions::table
+            .filter(executions::id.eq(Some(exec_id.to_string())))
+            .filter(executions::wait_till.eq(Some(wait_till))),
+    )
+    .set(executions::wait_till.eq(None::<NaiveDateTime>))
+    .execute(conn)
+}
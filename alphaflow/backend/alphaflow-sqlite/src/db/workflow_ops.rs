@@ -1,5 +1,6 @@
 // src/db/workflow_ops.rs
 
+use chrono::Utc;
 use diesel::prelude::*;
 use diesel::result::QueryResult;
 use crate::models::workflow::{Workflow, NewWorkflow};
@@ -38,6 +39,34 @@ pub fn update_workflow_name(conn: &mut SqliteConnection, wf_id: &str, new_name:
         .execute(conn)
 }
 
+pub fn update_workflow(conn: &mut SqliteConnection, wf_id: &str, wf: &NewWorkflow) -> QueryResult<usize> {
+    // UPDATE workflows SET ... WHERE id == Some(wf_id)，id 与 created_at 保持不变；
+    // static_data 由运行时单独写入（update_workflow_static_data），这里不更新
+    diesel::update(workflows::table.filter(workflows::id.eq(Some(wf_id.to_string()))))
+        .set((
+            workflows::name.eq(wf.name),
+            workflows::active.eq(wf.active),
+            workflows::nodes.eq(wf.nodes),
+            workflows::connections.eq(wf.connections),
+            workflows::settings.eq(wf.settings),
+            workflows::meta.eq(wf.meta),
+            workflows::owner_id.eq(wf.owner_id),
+            workflows::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+pub fn update_workflow_static_data(
+    conn: &mut SqliteConnection,
+    wf_id: &str,
+    static_data: Option<&str>,
+) -> QueryResult<usize> {
+    // UPDATE workflows SET static_data = ... WHERE id == Some(wf_id)，运行时数据不更新 updated_at
+    diesel::update(workflows::table.filter(workflows::id.eq(Some(wf_id.to_string()))))
+        .set(workflows::static_data.eq(static_data))
+        .execute(conn)
+}
+
 pub fn delete_workflow(conn: &mut SqliteConnection, wf_id: &str) -> QueryResult<usize> {
     // DELETE FROM workflows WHERE id == Some(wf_id)
     diesel::delete(workflows::table.filter(workflows::id.eq(Some(wf_id.to_string()))))
@@ -7,11 +7,21 @@ pub mod db;                 // 数据库 CRUD ops
 
 use diesel::r2d2::{self, ConnectionManager};
 use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use r2d2::Pool;
 
+/// 编译期嵌入的数据库迁移脚本 (migrations 目录)
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
 pub fn establish_connection_pool(database_url: &str) -> Pool<ConnectionManager<SqliteConnection>> {
     let manager = ConnectionManager::<SqliteConnection>::new(database_url);
     r2d2::Pool::builder()
         .build(manager)
         .expect("Failed to create DB pool.")
-}
\ No newline at end of file
+}
+
+/// 执行所有尚未执行的数据库迁移
+pub fn run_migrations(conn: &mut SqliteConnection) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    conn.run_pending_migrations(MIGRATIONS)?;
+    Ok(())
+}
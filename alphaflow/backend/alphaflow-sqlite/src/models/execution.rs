@@ -27,6 +27,10 @@ pub struct Execution {
 
     #[serde(with = "dt_seconds")]
     pub updated_at: NaiveDateTime,
+
+    /// 在 Wait 节点处暂停时，恢复运行的时间
+    #[serde(with = "dt_seconds_opt")]
+    pub wait_till: Option<NaiveDateTime>,
 }
 
 #[derive(Insertable)]
@@ -12,6 +12,7 @@ diesel::table! {
         started_by_user_id -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        wait_till -> Nullable<Timestamp>,
     }
 }
 
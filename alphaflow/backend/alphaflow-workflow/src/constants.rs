@@ -61,7 +61,9 @@ pub const EXECUTE_WORKFLOW_TRIGGER_NODE_TYPE: &str = "af-nodes-base.executeWorkf
 pub const CODE_NODE_TYPE: &str = "af-nodes-base.code";
 pub const FUNCTION_NODE_TYPE: &str = "af-nodes-base.function";
 pub const FUNCTION_ITEM_NODE_TYPE: &str = "af-nodes-base.functionItem";
-pub const MERGE_NODE_TYPE: &str = "af-nodes-base.merge";
+pub use alphaflow_nodes::condition::{IF_NODE_TYPE, SWITCH_NODE_TYPE};
+pub use alphaflow_nodes::merge::MERGE_NODE_TYPE;
+pub use alphaflow_nodes::split_in_batches::SPLIT_IN_BATCHES_NODE_TYPE;
 pub const AI_TRANSFORM_NODE_TYPE: &str = "af-nodes-base.aiTransform";
 pub const FORM_NODE_TYPE: &str = "af-nodes-base.form";
 pub const FORM_TRIGGER_NODE_TYPE: &str = "af-nodes-base.formTrigger";
@@ -0,0 +1,655 @@
+// src/definition.rs
+
+//! 手写的 YAML / TOML 工作流定义，便于把工作流放进 git 并审阅 diff。
+//!
+//! ```yaml
+//! name: Order sync
+//! settings:
+//!   max_parallelism: 4
+//! nodes:
+//!   fetch:
+//!     type: http
+//!     config: { url: "https://example.com/orders" }
+//!   check:
+//!     type: if
+//!     input_mapping: "body"
+//!     config: { condition: "status == `200`" }
+//! connections:
+//!   - fetch -> check
+//!   - from: check
+//!     output: "true"
+//!     to: notify
+//! ```
+//!
+//! - `nodes` 以节点名称为 key，`config` 是执行时交给节点的参数（[`Node::custom_config`]），
+//!   其余字段与 [`Node`] 同名（`retry` 对应 `retry_policy`）；
+//! - `connections` 可以写成 `"源 -> 目标"`、`"源:输出 -> 目标"`，或带 `from`、`to`、`output`、`input` 的表；
+//!   `input` 为该连接在目标节点父节点中的位置，缺省时按出现顺序；
+//! - `settings` 见 [`crate::executor::ExecutionOptions::from_settings`]，`pin_data` 以节点名称为 key。
+//!
+//! 加载时的语法错误和结构错误都带有行号（从 1 开始）。
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+use alphaflow_nodes::input_mapping::InputMapping;
+use alphaflow_nodes::node::{ItemExecutionMode, JoinMode, Node, OnError, RetryPolicy};
+use alphaflow_nodes::node_type::MAIN_OUTPUT;
+use alphaflow_nodes::NodeRegistry;
+use crate::validation::validate_workflow;
+use crate::workflow::Workflow;
+
+/// 定义文件的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionFormat {
+    Yaml,
+    Toml,
+}
+
+impl DefinitionFormat {
+    /// 根据文件扩展名判断格式（.yaml / .yml / .toml）
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(DefinitionFormat::Yaml),
+            "toml" => Some(DefinitionFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// 加载或写出定义时的错误，`line` / `column` 从 1 开始
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{}{}", format_location(.line, .column), .message)]
+pub struct DefinitionError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl DefinitionError {
+    fn new(line: Option<usize>, message: impl Into<String>) -> Self {
+        Self { line, column: None, message: message.into() }
+    }
+}
+
+fn format_location(line: &Option<usize>, column: &Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!("line {}, column {}: ", line, column),
+        (Some(line), None) => format!("line {}: ", line),
+        _ => String::new(),
+    }
+}
+
+/// 工作流定义文件的内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkflowDefinition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "is_empty_value")]
+    pub settings: Value,
+    #[serde(default)]
+    pub nodes: BTreeMap<String, NodeDefinition>,
+    #[serde(default)]
+    pub connections: Vec<ConnectionDefinition>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pin_data: BTreeMap<String, Value>,
+}
+
+/// 一个节点的定义
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeDefinition {
+    #[serde(rename = "type")]
+    pub node_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub on_error: OnError,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub execution_mode: ItemExecutionMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[i64; 2]>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub join_mode: JoinMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_mapping: Option<InputMapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<Value>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub parameters: Value,
+}
+
+/// 一条连接：`"源 -> 目标"` / `"源:输出 -> 目标"` 的简写，或完整的表
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ConnectionDefinition {
+    Short(String),
+    Full {
+        from: String,
+        to: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<usize>,
+    },
+}
+
+impl ConnectionDefinition {
+    /// 解析为 (源节点, 输出, 目标节点, 输入位置)
+    fn parts(&self) -> Result<(String, String, String, Option<usize>), String> {
+        match self {
+            ConnectionDefinition::Full { from, to, output, input } => Ok((
+                from.clone(),
+                output.clone().unwrap_or_else(|| MAIN_OUTPUT.to_string()),
+                to.clone(),
+                *input,
+            )),
+            ConnectionDefinition::Short(text) => {
+                let (source, target) = text
+                    .split_once("->")
+                    .ok_or_else(|| format!("Connection '{}' must look like 'source -> target'", text))?;
+                let (source, output) = match source.split_once(':') {
+                    Some((source, output)) => (source.trim(), output.trim()),
+                    None => (source.trim(), MAIN_OUTPUT),
+                };
+                let target = target.trim();
+                if source.is_empty() || output.is_empty() || target.is_empty() {
+                    return Err(format!("Connection '{}' must look like 'source -> target'", text));
+                }
+                Ok((source.to_string(), output.to_string(), target.to_string(), None))
+            }
+        }
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// 定义中各项所在的行号
+#[derive(Debug, Default)]
+struct LineIndex {
+    nodes: HashMap<String, usize>,
+    connections: Vec<usize>,
+    pin_data: HashMap<String, usize>,
+    settings: Option<usize>,
+}
+
+/// 解析定义文本，只检查语法和字段类型
+pub fn parse_definition(text: &str, format: DefinitionFormat) -> Result<WorkflowDefinition, DefinitionError> {
+    match format {
+        DefinitionFormat::Yaml => serde_yaml::from_str(text).map_err(|e| DefinitionError {
+            line: e.location().map(|l| l.line()),
+            column: e.location().map(|l| l.column()),
+            message: strip_location(e.to_string()),
+        }),
+        DefinitionFormat::Toml => toml::from_str(text).map_err(|e| DefinitionError {
+            line: e.line_col().map(|(line, _)| line + 1),
+            column: e.line_col().map(|(_, column)| column + 1),
+            message: strip_location(e.to_string()),
+        }),
+    }
+}
+
+/// 解析器的错误信息以 " at line X column Y" 结尾，位置已单独记录
+fn strip_location(message: String) -> String {
+    match message.rsplit_once(" at line ") {
+        Some((head, _)) => head.to_string(),
+        None => message,
+    }
+}
+
+/// 加载定义并转换为 [`Workflow`]，返回按行号排序的全部错误
+pub fn load_workflow(text: &str, format: DefinitionFormat) -> Result<Workflow, Vec<DefinitionError>> {
+    let definition = parse_definition(text, format).map_err(|e| vec![e])?;
+    let lines = index_lines(text, format);
+    build_workflow(&definition, &lines)
+}
+
+/// 与 [`load_workflow`] 相同，并用 [`validate_workflow`] 检查节点类型、环等问题，
+/// 错误级别的诊断定位到第一个相关节点所在的行
+pub fn load_workflow_checked(
+    text: &str,
+    format: DefinitionFormat,
+    registry: &NodeRegistry,
+) -> Result<Workflow, Vec<DefinitionError>> {
+    let wf = load_workflow(text, format)?;
+    let lines = index_lines(text, format);
+    let mut errors: Vec<DefinitionError> = validate_workflow(&wf, registry)
+        .into_iter()
+        .filter(|d| d.is_error())
+        .map(|d| {
+            let line = d.nodes.first().and_then(|n| lines.nodes.get(n)).copied();
+            DefinitionError::new(line, d.message)
+        })
+        .collect();
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.line);
+        return Err(errors);
+    }
+    Ok(wf)
+}
+
+/// 把 [`Workflow`] 转换为定义
+pub fn to_definition(wf: &Workflow) -> WorkflowDefinition {
+    let nodes = wf
+        .nodes
+        .iter()
+        .map(|(name, node)| {
+            let definition = NodeDefinition {
+                node_type: node.node_type_name.clone(),
+                display_name: node.display_name.clone(),
+                description: node.description.clone(),
+                disabled: node.disabled,
+                on_error: node.on_error,
+                execution_mode: node.execution_mode,
+                timeout_ms: node.timeout_ms,
+                position: node.position,
+                join_mode: node.join_mode,
+                input_mapping: node.input_mapping.clone(),
+                retry: node.retry_policy.clone(),
+                config: node.custom_config.clone(),
+                parameters: node.parameters.clone(),
+            };
+            (name.clone(), definition)
+        })
+        .collect();
+
+    let mut sources: Vec<&String> = wf.connections_by_source.keys().collect();
+    sources.sort();
+    let connections = sources
+        .into_iter()
+        .flat_map(|source| {
+            wf.connections_by_source[source].iter().map(move |c| {
+                // 只有多个父节点时才需要记录输入位置
+                let input = wf
+                    .connections_by_destination
+                    .get(&c.node)
+                    .filter(|parents| parents.len() > 1)
+                    .and_then(|parents| {
+                        parents.iter().position(|p| p.node == *source && p.output == c.output)
+                    });
+                ConnectionDefinition::Full {
+                    from: source.clone(),
+                    to: c.node.clone(),
+                    output: Some(c.output.clone()).filter(|o| o != MAIN_OUTPUT),
+                    input,
+                }
+            })
+        })
+        .collect();
+
+    WorkflowDefinition {
+        id: wf.id.clone(),
+        name: wf.name.clone(),
+        active: wf.active,
+        settings: wf.settings.clone(),
+        nodes,
+        connections,
+        pin_data: wf.pin_data.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+    }
+}
+
+/// 把 [`Workflow`] 写为定义文本
+pub fn write_workflow(wf: &Workflow, format: DefinitionFormat) -> Result<String, DefinitionError> {
+    let definition = to_definition(wf);
+    let text = match format {
+        DefinitionFormat::Yaml => serde_yaml::to_string(&definition).map_err(|e| e.to_string()),
+        // 先转为 toml::Value，由它负责把普通值排在表之前
+        DefinitionFormat::Toml => toml::Value::try_from(&definition)
+            .and_then(|value| toml::to_string(&value))
+            .map_err(|e| e.to_string()),
+    };
+    text.map_err(|message| DefinitionError::new(None, message))
+}
+
+fn build_workflow(definition: &WorkflowDefinition, lines: &LineIndex) -> Result<Workflow, Vec<DefinitionError>> {
+    let mut errors = Vec::new();
+    let mut wf = Workflow::new(definition.id.clone()).with_name(&definition.name);
+    wf.active = definition.active;
+    match &definition.settings {
+        Value::Null => {}
+        settings @ Value::Object(_) => wf.settings = settings.clone(),
+        _ => errors.push(DefinitionError::new(lines.settings, "'settings' must be a table")),
+    }
+
+    for (name, def) in &definition.nodes {
+        let line = lines.nodes.get(name).copied();
+        if name.trim().is_empty() {
+            errors.push(DefinitionError::new(line, "Node name cannot be empty"));
+            continue;
+        }
+        if def.node_type.trim().is_empty() {
+            errors.push(DefinitionError::new(line, format!("Node '{}' has an empty type", name)));
+        }
+        let mut node = Node::new(name, &def.node_type).with_parameters(def.parameters.clone());
+        node.display_name = def.display_name.clone();
+        node.description = def.description.clone();
+        node.disabled = def.disabled;
+        node.on_error = def.on_error;
+        node.execution_mode = def.execution_mode;
+        node.timeout_ms = def.timeout_ms;
+        node.position = def.position;
+        node.join_mode = def.join_mode;
+        node.input_mapping = def.input_mapping.clone();
+        node.retry_policy = def.retry.clone();
+        node.custom_config = def.config.clone();
+        wf.add_node(node);
+    }
+
+    let mut input_positions: HashMap<(String, String, String), usize> = HashMap::new();
+    let mut seen_inputs: HashMap<String, usize> = HashMap::new();
+    for (i, conn) in definition.connections.iter().enumerate() {
+        let line = lines.connections.get(i).copied();
+        let (source, output, target, input) = match conn.parts() {
+            Ok(parts) => parts,
+            Err(message) => {
+                errors.push(DefinitionError::new(line, message));
+                continue;
+            }
+        };
+        if let Err(e) = wf.connect_nodes_on_output(&source, &output, &target) {
+            errors.push(DefinitionError::new(line, format!("Connection {} -> {}: {}", source, target, e)));
+            continue;
+        }
+        let appearance = seen_inputs.entry(target.clone()).or_default();
+        let position = input.unwrap_or(*appearance);
+        *appearance += 1;
+        input_positions.insert((target, source, output), position);
+    }
+    for (target, parents) in wf.connections_by_destination.iter_mut() {
+        parents.sort_by_key(|p| {
+            input_positions.get(&(target.clone(), p.node.clone(), p.output.clone())).copied()
+        });
+    }
+
+    for (name, data) in &definition.pin_data {
+        if wf.nodes.contains_key(name) {
+            wf.pin_data.insert(name.clone(), data.clone());
+        } else {
+            errors.push(DefinitionError::new(
+                lines.pin_data.get(name).copied(),
+                format!("Pin data for unknown node {}", name),
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.line);
+        return Err(errors);
+    }
+    Ok(wf)
+}
+
+fn index_lines(text: &str, format: DefinitionFormat) -> LineIndex {
+    match format {
+        DefinitionFormat::Yaml => index_yaml_lines(text),
+        DefinitionFormat::Toml => index_toml_lines(text),
+    }
+}
+
+/// YAML 事件流中当前所在的容器
+enum Frame {
+    /// 映射，`key` 为正在读取其值的 key
+    Map { key: Option<String> },
+    /// 序列，`index` 为当前元素下标
+    Seq { index: usize },
+}
+
+/// 记录每个映射 key 和序列元素所在的行，路径形如 ["nodes", "fetch"]、["connections", "0"]
+#[derive(Default)]
+struct YamlLineCollector {
+    stack: Vec<Frame>,
+    lines: HashMap<Vec<String>, usize>,
+}
+
+impl YamlLineCollector {
+    fn path(&self) -> Vec<String> {
+        self.stack
+            .iter()
+            .map(|frame| match frame {
+                Frame::Map { key } => key.clone().unwrap_or_default(),
+                Frame::Seq { index } => index.to_string(),
+            })
+            .collect()
+    }
+
+    /// 一个节点开始；返回 true 表示它是映射的 key
+    fn begin(&mut self, key: Option<&str>, line: usize) -> bool {
+        match self.stack.last_mut() {
+            Some(Frame::Map { key: current @ None }) => {
+                *current = Some(key.unwrap_or_default().to_string());
+                let path = self.path();
+                self.lines.entry(path).or_insert(line);
+                true
+            }
+            Some(Frame::Seq { .. }) => {
+                let path = self.path();
+                self.lines.entry(path).or_insert(line);
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// 标量或别名：既可能是 key，也可能是完整的值
+    fn scalar(&mut self, key: Option<&str>, line: usize) {
+        if !self.begin(key, line) {
+            self.end_value();
+        }
+    }
+
+    /// 一个值读取完毕
+    fn end_value(&mut self) {
+        match self.stack.last_mut() {
+            Some(Frame::Map { key }) => *key = None,
+            Some(Frame::Seq { index }) => *index += 1,
+            None => {}
+        }
+    }
+}
+
+impl MarkedEventReceiver for YamlLineCollector {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::Scalar(value, ..) => self.scalar(Some(&value), mark.line()),
+            Event::Alias(_) => self.scalar(None, mark.line()),
+            Event::MappingStart(_) => {
+                self.begin(None, mark.line());
+                self.stack.push(Frame::Map { key: None });
+            }
+            Event::SequenceStart(_) => {
+                self.begin(None, mark.line());
+                self.stack.push(Frame::Seq { index: 0 });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.end_value();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn index_yaml_lines(text: &str) -> LineIndex {
+    let mut collector = YamlLineCollector::default();
+    // 语法错误已由 serde_yaml 报告，这里只尽量收集行号
+    let _ = Parser::new(text.chars()).load(&mut collector, false);
+
+    let mut index = LineIndex::default();
+    let mut connections: Vec<(usize, usize)> = Vec::new();
+    for (path, line) in collector.lines {
+        match path.as_slice() {
+            [section, name] if section == "nodes" => {
+                index.nodes.insert(name.clone(), line);
+            }
+            [section, name] if section == "pin_data" => {
+                index.pin_data.insert(name.clone(), line);
+            }
+            [section, i] if section == "connections" => {
+                if let Ok(i) = i.parse() {
+                    connections.push((i, line));
+                }
+            }
+            [section] if section == "settings" => index.settings = Some(line),
+            _ => {}
+        }
+    }
+    connections.sort();
+    index.connections = connections.into_iter().map(|(_, line)| line).collect();
+    index
+}
+
+/// 只用于收集 TOML 行号的结构
+#[derive(Deserialize)]
+struct TomlSpans {
+    #[serde(default)]
+    nodes: BTreeMap<toml::Spanned<String>, toml::Value>,
+    #[serde(default)]
+    connections: Vec<toml::Spanned<toml::Value>>,
+    #[serde(default)]
+    pin_data: BTreeMap<toml::Spanned<String>, toml::Value>,
+}
+
+fn index_toml_lines(text: &str) -> LineIndex {
+    let line_of = |offset: usize| text[..offset.min(text.len())].matches('\n').count() + 1;
+    let mut index = LineIndex {
+        settings: text
+            .lines()
+            .position(|l| l.trim() == "[settings]" || l.trim_start().starts_with("settings"))
+            .map(|i| i + 1),
+        ..Default::default()
+    };
+    let Ok(spans) = toml::from_str::<TomlSpans>(text) else {
+        return index;
+    };
+    index.nodes = spans.nodes.keys().map(|k| (k.get_ref().clone(), line_of(k.start()))).collect();
+    index.pin_data = spans.pin_data.keys().map(|k| (k.get_ref().clone(), line_of(k.start()))).collect();
+
+    // `[[connections]]` 形式的元素没有位置信息，按表头出现的顺序定位
+    let mut headers = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| l.trim() == "[[connections]]")
+        .map(|(i, _)| i + 1);
+    index.connections = spans
+        .connections
+        .iter()
+        .map(|c| if c.end() > 0 { Some(line_of(c.start())) } else { headers.next() })
+        .map(Option::unwrap_or_default)
+        .collect();
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use alphaflow_nodes::registry_helper::register_all_nodes;
+    use crate::workflow::Connection;
+
+    const YAML: &str = r#"name: Order sync
+settings:
+  max_parallelism: 4
+nodes:
+  fetch:
+    type: http
+    config: { url: "https://example.com/orders" }
+    retry: { max_attempts: 2 }
+  check:
+    type: if
+    input_mapping: "body"
+    config: { condition: "status == `200`" }
+  join:
+    type: af-nodes-base.merge
+connections:
+  - fetch -> check
+  - from: check
+    output: "false"
+    to: join
+    input: 1
+  - from: check
+    output: "true"
+    to: join
+    input: 0
+pin_data:
+  fetch: [{ status: 200 }]
+"#;
+
+    #[test]
+    fn test_load_yaml_definition() {
+        let wf = load_workflow(YAML, DefinitionFormat::Yaml).unwrap();
+        assert_eq!(wf.name, "Order sync");
+        assert_eq!(wf.settings, json!({ "max_parallelism": 4 }));
+        assert_eq!(wf.nodes["fetch"].custom_config, Some(json!({ "url": "https://example.com/orders" })));
+        assert_eq!(wf.nodes["fetch"].retry_policy.as_ref().unwrap().max_attempts, 2);
+        assert_eq!(wf.nodes["check"].input_mapping, Some(InputMapping::Single("body".into())));
+        assert_eq!(wf.connections_by_source["check"], vec![
+            Connection::new("join", "false"),
+            Connection::new("join", "true"),
+        ]);
+        assert_eq!(wf.connections_by_destination["join"], vec![
+            Connection::new("check", "true"),
+            Connection::new("check", "false"),
+        ]);
+        assert_eq!(wf.pin_data["fetch"], json!([{ "status": 200 }]));
+    }
+
+    #[test]
+    fn test_round_trip_through_yaml_and_toml() {
+        let wf = load_workflow(YAML, DefinitionFormat::Yaml).unwrap();
+        for format in [DefinitionFormat::Yaml, DefinitionFormat::Toml] {
+            let text = write_workflow(&wf, format).unwrap();
+            let loaded = load_workflow(&text, format).unwrap_or_else(|e| panic!("{}\n{:?}", text, e));
+            assert_eq!(loaded, wf, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_errors_have_line_numbers() {
+        let yaml = "nodes:\n  a:\n    type: http\n  b:\n    type: \"\"\nconnections:\n  - a -> b\n  - a -> missing\n  - nonsense\n";
+        let errors = load_workflow(yaml, DefinitionFormat::Yaml).unwrap_err();
+        let lines: Vec<Option<usize>> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![Some(4), Some(8), Some(9)]);
+        assert!(errors[1].to_string().starts_with("line 8: Connection a -> missing"));
+
+        let toml = "[nodes.a]\ntype = \"http\"\n\n[[connections]]\nfrom = \"a\"\nto = \"a\"\n\n[[connections]]\nfrom = \"a\"\nto = \"nowhere\"\n";
+        let errors = load_workflow(toml, DefinitionFormat::Toml).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, Some(8));
+
+        let errors = load_workflow("nodes:\n  a:\n    typ: http\n", DefinitionFormat::Yaml).unwrap_err();
+        assert_eq!(errors[0].line, Some(3));
+        assert!(errors[0].to_string().starts_with("line 3, column 5: nodes.a: unknown field `typ`"), "{}", errors[0]);
+
+        let mut registry = NodeRegistry::new();
+        register_all_nodes(&mut registry);
+        let errors = load_workflow_checked("nodes:\n  a:\n    type: http\n  b:\n    type: nope\n", DefinitionFormat::Yaml, &registry)
+            .unwrap_err();
+        assert_eq!(errors[0].line, Some(4));
+    }
+}
@@ -491,6 +491,7 @@ fn test_complex_nodedata_workflow() {
             display_name: None,
             description: None,
             custom_config: None,
+            ..Default::default()
         },
     );
     graph.add_node(
@@ -504,6 +505,7 @@ fn test_complex_nodedata_workflow() {
             display_name: None,
             description: None,
             custom_config: None,
+            ..Default::default()
         },
     );
     graph.add_node(
@@ -517,6 +519,7 @@ fn test_complex_nodedata_workflow() {
             display_name: None,
             description: None,
             custom_config: None,
+            ..Default::default()
         },
     );
     graph.add_node(
@@ -530,6 +533,7 @@ fn test_complex_nodedata_workflow() {
             display_name: None,
             description: None,
             custom_config: None,
+            ..Default::default()
         },
     );
 
@@ -0,0 +1,58 @@
+// src/events.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use crate::run_result::RunError;
+
+/// 发送执行事件的一端，通过 [`crate::executor::ExecutionOptions::with_events`] 交给执行器
+pub type EventSender = mpsc::UnboundedSender<ExecutionEvent>;
+/// 接收执行事件的一端
+pub type EventReceiver = mpsc::UnboundedReceiver<ExecutionEvent>;
+
+/// 创建一对执行事件通道
+pub fn event_channel() -> (EventSender, EventReceiver) {
+    mpsc::unbounded_channel()
+}
+
+/// 节点输出的概要信息，避免在事件中传递完整的输出数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OutputSummary {
+    /// 输出的数据项数量
+    pub item_count: usize,
+    /// 传出数据的输出名称
+    pub outputs: Vec<String>,
+}
+
+/// 工作流运行过程中产生的事件，按发生顺序发送
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// 运行开始
+    RunStarted { workflow_id: Option<String> },
+    /// 节点开始执行
+    NodeStarted { node: String },
+    /// 节点执行失败，将在 `delay_ms` 毫秒后进行第 `next_attempt` 次执行
+    NodeRetrying {
+        node: String,
+        next_attempt: u32,
+        delay_ms: u64,
+        error: String,
+    },
+    /// 节点执行成功（`pinned` 为 true 时节点没有执行，输出来自 Pin 数据）
+    NodeFinished {
+        node: String,
+        summary: OutputSummary,
+        #[serde(default)]
+        pinned: bool,
+    },
+    /// Wait 节点暂停了运行，运行将在 `wait_till` 之后由任务引擎恢复
+    NodeWaiting { node: String, wait_till: DateTime<Utc> },
+    /// 节点最终执行失败
+    NodeFailed { node: String, error: RunError },
+    /// 运行暂停在 Wait 节点上，之后由任务引擎或外部请求恢复；字段与 [`crate::run_result::WaitingState`] 相同。
+    /// 暂停的运行不会发送 `RunFinished`
+    RunWaiting { nodes: Vec<String>, wait_till: DateTime<Utc> },
+    /// 运行结束，`error` 为空表示运行成功
+    RunFinished { error: Option<RunError> },
+}
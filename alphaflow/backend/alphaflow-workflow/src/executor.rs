@@ -1,145 +1,2200 @@
-// // src/executor.rs
-
-// use std::collections::{HashMap, VecDeque};
-// use serde_json::{json, Value};
-// use log::{info, warn, error};
-// use crate::workflow::Workflow;
-// use alphaflow_nodes::node::Node;
-// use crate::jmes_runtime::{compile_and_search, JmesMappingError};
-// use alphaflow_nodes::node_type::{NodeExecutionContext, build_context};
-// use crate::waiting_queue::WaitingQueue;
-
-
-// /// 每个待执行项：节点名称及其输入数据 (JSON)
-// #[derive(Debug)]
-// pub struct ExecutionItem {
-//     pub node_name: String,
-//     pub input_data: Value,
-// }
-
-// /// 执行上下文：包含执行栈、节点最终结果和一个等待队列（用于多父节点合并）
-// #[derive(Debug)]
-// pub struct ExecutionData {
-//     pub execution_stack: VecDeque<ExecutionItem>,
-//     pub results: HashMap<String, Value>,
-//     pub waiting_queue: WaitingQueue,
-//     pub canceled: bool,
-// }
-
-// /// 主执行函数：遍历执行栈，处理每个节点
-// ///
-// /// 参数说明：
-// /// - workflow: 工作流，包含所有节点、连接和 pin_data；
-// /// - globals, env: 全局变量和环境数据（这里作为 JSON 对象传入，可扩展）；
-// /// - exec_data: 执行上下文，包含执行栈、结果和等待队列；
-// /// - required_parent_count: 子节点至少需要来自多少个父节点的数据后才开始执行（用于多父节点合并）。
-// pub fn run_workflow(
-//     workflow: &Workflow,
-//     globals: Value,
-//     env: Value,
-//     exec_data: &mut ExecutionData,
-//     required_parent_count: usize,
-// ) -> Result<(), String> {
-//     while let Some(item) = exec_data.execution_stack.pop_front() {
-//         if exec_data.canceled {
-//             warn!("Execution canceled, stopping.");
-//             return Ok(());
-//         }
-
-//         // 查找当前节点（内嵌的 Node 定义）
-//         let node = match workflow.nodes.get(&item.node_name) {
-//             Some(n) => n,
-//             None => {
-//                 error!("Node '{}' not found, skipping.", item.node_name);
-//                 continue;
-//             }
-//         };
-//         if node.disabled {
-//             info!("Node '{}' is disabled, skipping.", node.name);
-//             continue;
-//         }
-
-//         info!("Executing node '{}', input: {}", node.name, item.input_data);
-//         // 模拟节点执行：如果输入为字符串，则在末尾添加 "-> processed_by_NODE"
-//         let node_output = simulate_node_exec(node, &item.input_data);
-//         exec_data.results.insert(node.name.clone(), node_output.clone());
-
-//         // 遍历所有下游连接
-//         let outgoings = workflow.get_outgoings(&node.name);
-//         for conn in outgoings {
-//             let child_name = &conn.child_name;
-//             // 查找子节点配置
-//             let child_node = match workflow.nodes.get(child_name) {
-//                 Some(n) => n,
-//                 None => {
-//                     error!("Child node '{}' not found.", child_name);
-//                     continue;
-//                 }
-//             };
-
-//             // 如果子节点有 PinData，则直接使用 PinData（优先级最高）
-//             if let Some(pin_val) = workflow.pin_data.get(child_name) {
-//                 info!("Child node '{}' is pinned. Using pinData: {}", child_name, pin_val);
-//                 exec_data.execution_stack.push_back(ExecutionItem {
-//                     node_name: child_name.clone(),
-//                     input_data: pin_val.clone(),
-//                 });
-//             } else {
-//                 // 否则，将当前父节点输出加入等待队列（用于多父节点合并）
-//                 exec_data.waiting_queue.add(child_name, &node.name, node_output.clone());
-//                 // 如果等待队列中数据足够，则合并后生成统一输入
-//                 if exec_data.waiting_queue.is_ready(child_name, required_parent_count) {
-//                     let merged = exec_data.waiting_queue.merge(child_name)
-//                         .ok_or_else(|| format!("Failed to merge waiting data for child '{}'", child_name))?;
-//                     // 构造映射上下文，将合并结果放入 "$json" 字段中
-//                     let mapping_context = json!({ "$json": merged });
-//                     // 如果子节点配置了映射表达式，则调用映射引擎
-//                     if let Some(mapping_expr) = &child_node.input_mapping {
-//                         info!("Child node '{}' has mapping: {}", child_name, mapping_expr);
-//                         match compile_and_search(mapping_expr, &mapping_context) {
-//                             Ok(mapped) => {
-//                                 info!("Mapped output for child '{}': {}", child_name, mapped);
-//                                 exec_data.execution_stack.push_back(ExecutionItem {
-//                                     node_name: child_name.clone(),
-//                                     input_data: mapped,
-//                                 });
-//                             }
-//                             Err(e) => {
-//                                 error!("Mapping error for child '{}': {:?}", child_name, e);
-//                                 return Err(format!("Mapping error for child '{}': {:?}", child_name, e));
-//                             }
-//                         }
-//                     } else {
-//                         // 若无映射表达式，则直接将合并后的结果推入执行栈
-//                         info!("Child node '{}' has no mapping; using merged input.", child_name);
-//                         exec_data.execution_stack.push_back(ExecutionItem {
-//                             node_name: child_name.clone(),
-//                             input_data: merged,
-//                         });
-//                     }
-//                 }
-//             }
-//         }
-//     }
-//     Ok(())
-// }
-
-// /// 模拟节点执行：
-// /// - 如果输入为字符串，则返回 "input -> processed_by_NODE"；
-// /// - 如果输入为对象，则在对象中插入 "processed_by" 字段。
-// fn simulate_node_exec(node: &Node, input: &Value) -> Value {
-//     if input.is_string() {
-//         let s = input.as_str().unwrap();
-//         Value::String(format!("{} -> processed_by_{}", s, node.name))
-//     } else if input.is_object() {
-//         let mut obj = input.as_object().unwrap().clone();
-//         obj.insert("processed_by".to_string(), Value::String(node.name.clone()));
-//         Value::Object(obj)
-//     } else if input.is_array() {
-//         let mut arr = input.as_array().unwrap().clone();
-//         arr.push(Value::String(format!("processed_by_{}", node.name)));
-//         Value::Array(arr)
-//     } else {
-//         Value::String(format!("(unknown) -> processed_by_{}", node.name))
-//     }
-// }
\ No newline at end of file
+// src/executor.rs
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use log::{debug, error, warn};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use alphaflow_nodes::input_mapping::InputMapping;
+use alphaflow_nodes::item::{NodeItem, PairedItem};
+use alphaflow_nodes::node::{ItemExecutionMode, JoinMode, Node, OnError, RetryPolicy};
+use alphaflow_nodes::node_type::{
+    NodeError, NodeExecutionContext, NodeOutput, NodeType, WorkflowRunner, ERROR_OUTPUT, MAIN_OUTPUT,
+};
+use alphaflow_nodes::split_in_batches::split_in_batches_params::SplitInBatchesParams;
+use alphaflow_nodes::split_in_batches::{DONE_OUTPUT, LOOP_OUTPUT};
+use alphaflow_nodes::static_data::StaticDataStore;
+use alphaflow_nodes::NodeRegistry;
+use crate::constants::EXECUTE_WORKFLOW_NODE_TYPE;
+use crate::events::{EventSender, ExecutionEvent, OutputSummary};
+use crate::expression::{self, ExpressionScope, NodeExpressions, NodeResults};
+use crate::jq;
+use crate::persistence::{ExecutionRecorder, ExecutionSession};
+use crate::run_context::RunContext;
+use crate::run_result::{NodeAttempt, NodeRunRecord, RunError, RunResult, WaitingState};
+use crate::wait::pause_until;
+use crate::waiting_queue::WaitingQueue;
+use crate::workflow::Workflow;
+
+/// 默认的最大并发节点数
+pub const DEFAULT_MAX_PARALLELISM: usize = 8;
+
+/// 工作流执行选项
+#[derive(Debug, Clone)]
+pub struct ExecutionOptions {
+    /// 同一时刻最多并发执行的节点数（至少为 1）
+    pub max_parallelism: usize,
+    /// 为 true 时，设置了 Pin 数据的节点不再执行，直接以 Pin 数据作为输出
+    pub use_pin_data: bool,
+    /// 整个运行的超时时间，为空时不限制
+    pub timeout: Option<Duration>,
+    /// 取消令牌：取消后中止所有正在执行的节点，运行以 `Cancelled` 错误结束
+    pub cancel_token: CancellationToken,
+    /// 执行事件的接收方，为空时不发送事件
+    pub events: Option<EventSender>,
+    /// 运行记录写入 executions 表，为空时不记录
+    pub recorder: Option<ExecutionRecorder>,
+    /// 供 Execute Workflow 节点执行子工作流（见 [`crate::sub_workflow::SubWorkflowRunner`]），
+    /// 为空时子工作流节点执行失败
+    pub workflows: Option<Arc<dyn WorkflowRunner>>,
+    /// 允许节点和映射表达式读取的环境变量名（白名单，见 [`crate::run_context::RunContext`]）
+    pub env_vars: Vec<String>,
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        Self {
+            max_parallelism: DEFAULT_MAX_PARALLELISM,
+            use_pin_data: false,
+            timeout: None,
+            cancel_token: CancellationToken::new(),
+            events: None,
+            recorder: None,
+            workflows: None,
+            env_vars: Vec::new(),
+        }
+    }
+}
+
+impl ExecutionOptions {
+    /// 从工作流 settings 中读取执行选项，缺失的字段使用默认值
+    ///
+    /// 支持的字段：
+    /// - `max_parallelism`: 最大并发节点数
+    /// - `use_pin_data`: 是否使用 Pin 数据代替节点执行
+    /// - `timeout_ms`: 整个运行的超时时间（毫秒）
+    pub fn from_settings(settings: &Value) -> Self {
+        Self::default().with_settings(settings)
+    }
+
+    /// 用工作流 settings 中的字段（见 [`Self::from_settings`]）覆盖对应的选项，其余选项保持不变
+    pub fn with_settings(mut self, settings: &Value) -> Self {
+        if let Some(n) = settings.get("max_parallelism").and_then(Value::as_u64) {
+            self = self.with_max_parallelism(n as usize);
+        }
+        if let Some(use_pin_data) = settings.get("use_pin_data").and_then(Value::as_bool) {
+            self = self.with_use_pin_data(use_pin_data);
+        }
+        if let Some(ms) = settings.get("timeout_ms").and_then(Value::as_u64) {
+            self = self.with_timeout(Duration::from_millis(ms));
+        }
+        self
+    }
+
+    /// 设置最大并发节点数，0 会被视为 1
+    pub fn with_max_parallelism(mut self, max_parallelism: usize) -> Self {
+        self.max_parallelism = max_parallelism.max(1);
+        self
+    }
+
+    /// 设置是否使用 Pin 数据
+    pub fn with_use_pin_data(mut self, use_pin_data: bool) -> Self {
+        self.use_pin_data = use_pin_data;
+        self
+    }
+
+    /// 设置整个运行的超时时间
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 使用外部的取消令牌
+    pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+        self.cancel_token = cancel_token;
+        self
+    }
+
+    /// 把执行事件发送到指定通道（见 [`crate::events::event_channel`]）
+    pub fn with_events(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// 把运行记录写入 executions 表
+    pub fn with_recorder(mut self, recorder: ExecutionRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// 设置执行子工作流的方式
+    pub fn with_workflow_runner(mut self, runner: Arc<dyn WorkflowRunner>) -> Self {
+        self.workflows = Some(runner);
+        self
+    }
+
+    /// 设置允许读取的环境变量名，不在名单中的环境变量对节点不可见
+    pub fn with_env_vars<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.env_vars = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 发送执行事件，接收方已关闭时忽略
+    pub(crate) fn emit(&self, event: ExecutionEvent) {
+        emit(self.events.as_ref(), event);
+    }
+}
+
+fn emit(events: Option<&EventSender>, event: ExecutionEvent) {
+    if let Some(events) = events {
+        let _ = events.send(event);
+    }
+}
+
+/// 后台运行中的工作流，可以随时取消或等待其结束
+#[derive(Debug)]
+pub struct RunHandle {
+    cancel_token: CancellationToken,
+    handle: JoinHandle<RunResult>,
+}
+
+impl RunHandle {
+    /// 在后台启动工作流运行
+    pub fn spawn(workflow: Workflow, registry: Arc<NodeRegistry>, options: ExecutionOptions) -> Self {
+        let cancel_token = options.cancel_token.clone();
+        let handle = tokio::spawn(async move {
+            workflow.execute_with_options(&registry, &options).await
+        });
+        Self { cancel_token, handle }
+    }
+
+    /// 取消运行：正在执行的节点会被中止，运行结果带有 `Cancelled` 错误
+    pub fn cancel(&self) {
+        self.cancel_token.cancel();
+    }
+
+    /// 本次运行使用的取消令牌
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.cancel_token.clone()
+    }
+
+    /// 运行是否已经结束
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// 等待运行结束并返回运行结果
+    pub async fn wait(self) -> RunResult {
+        match self.handle.await {
+            Ok(result) => result,
+            Err(e) => {
+                let err = NodeError::ExecutionFailed(format!("Workflow task failed to complete: {e}"));
+                RunResult {
+                    error: Some(RunError::from_node_error(None, &err)),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
+/// 节点从某个输出传出的数据
+#[derive(Debug, Clone)]
+struct Emission {
+    output: String,
+    data: Value,
+    items: Vec<NodeItem>,
+}
+
+/// 节点执行成功后的结果：记录到运行结果中的数据，以及从各输出传出的数据
+#[derive(Debug)]
+struct NodeResult {
+    data: Value,
+    emissions: Vec<Emission>,
+}
+
+impl NodeResult {
+    fn summary(&self) -> OutputSummary {
+        OutputSummary {
+            item_count: NodeItem::from_value(&self.data).len(),
+            outputs: self.emissions.iter().map(|e| e.output.clone()).collect(),
+        }
+    }
+}
+
+/// 节点执行任务的返回值：(节点 ID, 执行结果, 每次执行尝试的记录)
+type NodeTaskResult = (String, Result<NodeResult, NodeError>, Vec<NodeAttempt>);
+
+/// 单个节点执行时使用的配置
+struct NodeRunSettings {
+    retry_policy: Option<RetryPolicy>,
+    execution_mode: ItemExecutionMode,
+    /// 单次执行的超时时间
+    timeout: Option<Duration>,
+    events: Option<EventSender>,
+    /// 逐项执行时按各输入数据项分别解析表达式后的参数，为空时使用上下文中的参数
+    item_parameters: Vec<Value>,
+}
+
+/// 可以执行的节点及其输入
+struct ReadyNode {
+    node_id: String,
+    /// 按父节点顺序排列的父节点输出，每个父节点一项，没有输出的父节点（被跳过的分支等）为 None
+    inputs: Vec<Option<Value>>,
+    /// 各父节点输出的数据项，按父节点顺序拼接
+    items: Vec<NodeItem>,
+}
+
+/// 循环节点的迭代状态
+#[derive(Default)]
+struct LoopState {
+    /// 尚未传给循环体的批次
+    pending: VecDeque<Vec<NodeItem>>,
+    /// 循环体通过回边送回的数据项，全部批次结束后从 "done" 输出传出
+    collected: Vec<NodeItem>,
+}
+
+/// 执行过程中的调度状态
+struct Scheduler<'a> {
+    workflow: &'a Workflow,
+    /// 循环回边 (source, target)，见 [`Workflow::loop_back_edges`]
+    back_edges: HashSet<(String, String)>,
+    /// 循环节点 ID -> 迭代状态
+    loops: HashMap<String, LoopState>,
+    /// 收集各节点来自父节点的输出
+    waiting: WaitingQueue,
+    /// 已经可以执行、等待空闲并发槽位的节点
+    ready: VecDeque<ReadyNode>,
+    /// 已经调度（启动执行或被跳过）的节点，保证每个节点在一次运行中只执行一次；
+    /// 循环体中的节点在每个批次开始时被移出，从而对每个批次执行一次
+    scheduled: HashSet<String>,
+    /// 节点 ID -> 输出数据
+    results: HashMap<String, Value>,
+}
+
+impl<'a> Scheduler<'a> {
+    /// `seeds` 中的节点视为已经执行完成：不会再执行，其输出从激活的输出传给子节点
+    fn new(workflow: &'a Workflow, seeds: HashMap<String, NodeOutput>) -> Self {
+        let mut scheduler = Self {
+            workflow,
+            back_edges: workflow.loop_back_edges(),
+            loops: HashMap::new(),
+            waiting: WaitingQueue::default(),
+            ready: VecDeque::new(),
+            scheduled: HashSet::new(),
+            results: HashMap::new(),
+        };
+        let mut start_nodes: Vec<String> = workflow
+            .nodes
+            .keys()
+            .filter(|node_id| scheduler.input_parents(node_id).is_empty() && !seeds.contains_key(*node_id))
+            .cloned()
+            .collect();
+        // HashMap 的遍历顺序不固定，排序后保证起始节点的调度顺序稳定
+        start_nodes.sort();
+        let mut seeds: Vec<(String, NodeOutput)> = seeds.into_iter().collect();
+        seeds.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (node_id, _) in &seeds {
+            scheduler.scheduled.insert(node_id.clone());
+        }
+        for (node_id, seed) in seeds {
+            let items = seed.output_items();
+            let emissions = seed
+                .active_outputs()
+                .into_iter()
+                .map(|output| Emission { output, data: seed.data.clone(), items: items.clone() })
+                .collect();
+            scheduler.complete(&node_id, NodeResult { data: seed.data, emissions });
+        }
+        for node_id in start_nodes {
+            scheduler.check_ready(&node_id);
+        }
+        scheduler
+    }
+
+    /// 根据节点的 join_mode 检查其是否可以调度：
+    ///
+    /// - `WaitAll`：所有父节点都已结束；
+    /// - `WaitAny`：任意一个父节点产生了输出；
+    /// - `WaitN(n)`：已收到 n 个父节点的输出。
+    ///
+    /// 所有父节点都已结束但仍未满足条件时，只要有输出就执行；完全没有输出（例如父节点均被禁用）则跳过。
+    /// 禁用的节点在满足条件后同样被跳过。
+    fn check_ready(&mut self, node_id: &str) {
+        if self.scheduled.contains(node_id) {
+            return;
+        }
+        let Some(node_cfg) = self.workflow.nodes.get(node_id) else {
+            return;
+        };
+        let parents = self.input_parents(node_id);
+        let received = self.waiting.received_count(node_id);
+        let all_finished = self.waiting.finished_count(node_id) >= parents.len();
+
+        let satisfied = match node_cfg.join_mode {
+            JoinMode::WaitAll => all_finished,
+            JoinMode::WaitAny => received >= 1,
+            JoinMode::WaitN(n) => received >= n.max(1),
+        };
+        if !satisfied && !all_finished {
+            return;
+        }
+
+        self.scheduled.insert(node_id.to_string());
+        let items = self.waiting.take_ordered_items(node_id, &parents);
+        let inputs = self.waiting.take_by_position(node_id, &parents);
+        if node_cfg.disabled || (!parents.is_empty() && inputs.iter().all(Option::is_none)) {
+            debug!("Skipping node '{}'", node_id);
+            self.notify_children(node_id, None);
+            return;
+        }
+        self.ready.push_back(ReadyNode {
+            node_id: node_id.to_string(),
+            inputs,
+            items,
+        });
+    }
+
+    /// 节点执行完成，记录输出并通过激活的输出通知子节点
+    fn complete(&mut self, node_id: &str, result: NodeResult) {
+        self.results.insert(node_id.to_string(), result.data);
+        self.notify_children(node_id, Some(&result.emissions));
+    }
+
+    /// 把节点结果传递给所有子节点：通过激活的输出连接的子节点收到该输出的数据，
+    /// 其余子节点（以及节点被跳过、即 `emissions` 为 None 时的全部子节点）只记录该父节点已结束，
+    /// 未被激活的分支因此会被逐级跳过
+    ///
+    /// 循环节点传出批次（"loop" 输出）时不通知其余子节点，它们要等到 "done" 输出才结束等待；
+    /// 通过回边连接的循环节点在所有回边父节点结束后进入下一个批次（见 [`Scheduler::check_loop_iteration`]）。
+    fn notify_children(&mut self, node_id: &str, emissions: Option<&[Emission]>) {
+        let emissions = emissions.unwrap_or_default();
+        let iterating = emissions.iter().any(|e| e.output == LOOP_OUTPUT) && self.workflow.is_loop_node(node_id);
+        for child in unique(self.workflow.get_children(node_id)) {
+            let back_edge = self.back_edges.contains(&(node_id.to_string(), child.clone()));
+            if self.scheduled.contains(&child) && !back_edge {
+                continue;
+            }
+            let connected = self.workflow.outputs_between(node_id, &child);
+            let emission = emissions.iter().find(|e| connected.contains(&e.output));
+            match emission {
+                Some(e) => self.waiting.add_with_items(&child, node_id, e.data.clone(), e.items.clone()),
+                None if iterating => continue,
+                None => self.waiting.mark_finished(&child, node_id),
+            }
+            if back_edge {
+                self.check_loop_iteration(&child);
+            } else {
+                self.check_ready(&child);
+            }
+        }
+    }
+
+    /// 决定节点首次执行时机的父节点：去掉循环回边，且去除重复项
+    fn input_parents(&self, node_id: &str) -> Vec<String> {
+        unique(self.workflow.get_parents(node_id))
+            .into_iter()
+            .filter(|parent| !self.back_edges.contains(&(parent.clone(), node_id.to_string())))
+            .collect()
+    }
+
+    /// 循环体的一个批次结束（所有回边父节点都已结束）时，把循环节点连同送回的数据项重新加入就绪队列
+    fn check_loop_iteration(&mut self, loop_id: &str) {
+        let parents: Vec<String> = unique(self.workflow.get_parents(loop_id))
+            .into_iter()
+            .filter(|parent| self.back_edges.contains(&(parent.clone(), loop_id.to_string())))
+            .collect();
+        if self.waiting.finished_count(loop_id) < parents.len() {
+            return;
+        }
+        let items = self.waiting.take_ordered_items(loop_id, &parents);
+        let inputs = self.waiting.take_by_position(loop_id, &parents);
+        self.ready.push_back(ReadyNode {
+            node_id: loop_id.to_string(),
+            inputs,
+            items,
+        });
+    }
+
+    /// 执行循环节点的一步：首次执行时把输入数据项切分为批次，之后收集循环体送回的数据项；
+    /// 还有批次时重置循环体并从 "loop" 输出传出下一个批次，否则从 "done" 输出传出收集到的全部数据项
+    fn step_loop(&mut self, ready: ReadyNode, run: &RunContext) -> Result<NodeResult, NodeError> {
+        let ReadyNode { node_id, inputs, items } = ready;
+        let state = match self.loops.entry(node_id.clone()) {
+            Entry::Occupied(entry) => {
+                let state = entry.into_mut();
+                state.collected.extend(items);
+                state
+            }
+            Entry::Vacant(entry) => {
+                let node_cfg = &self.workflow.nodes[&node_id];
+                let params = SplitInBatchesParams::parse(node_cfg.custom_config.as_ref().unwrap_or(&Value::Null))?;
+                let items = match &node_cfg.input_mapping {
+                    Some(mapping) => {
+                        let merged_input = merge_inputs(&inputs);
+                        let scope = ExpressionScope::new(&merged_input).with_nodes(&self.results).with_run(run);
+                        NodeItem::from_value(&apply_input_mapping(&node_id, mapping, &scope)?)
+                    }
+                    None => items,
+                };
+                entry.insert(LoopState { pending: params.batches(items).into(), ..Default::default() })
+            }
+        };
+
+        let (output, items) = match state.pending.pop_front() {
+            Some(batch) => {
+                self.reset_loop_body(&node_id);
+                (LOOP_OUTPUT, batch)
+            }
+            None => {
+                let state = self.loops.remove(&node_id).unwrap_or_default();
+                (DONE_OUTPUT, state.collected)
+            }
+        };
+        let data = NodeItem::to_value(&items);
+        let emission = Emission { output: output.to_string(), data: data.clone(), items };
+        Ok(NodeResult { data, emissions: vec![emission] })
+    }
+
+    /// 清除循环体中各节点的调度状态，使其可以处理下一个批次；嵌套循环的状态一并清除
+    fn reset_loop_body(&mut self, loop_id: &str) {
+        for node_id in self.workflow.loop_body(loop_id) {
+            self.scheduled.remove(&node_id);
+            self.waiting.clear(&node_id);
+            self.loops.remove(&node_id);
+        }
+    }
+}
+
+/// 执行工作流：满足等待条件（见 [`JoinMode`]）的节点会被并发启动（受 `max_parallelism` 限制），
+/// 每个节点在一次运行中最多执行一次（失败后按节点的 [`RetryPolicy`] 重试）。
+///
+/// 节点最终失败时按其 [`OnError`] 处理：`StopWorkflow` 会中止其余正在执行的节点，
+/// 错误与已经得到的输出一起返回；其余方式把 `{ "error": ... }` 作为该节点的输出继续执行。
+///
+/// 取消令牌被触发或超过运行超时时间时，中止所有正在执行的节点，
+/// 分别以 `Cancelled` / `Timeout` 错误和已经得到的输出结束，不受节点的 on_error 配置影响。
+///
+/// 需要长时间等待的 Wait 节点不会执行，运行在其余节点结束后以 `waiting` 暂停（见 [`crate::wait`]）。
+pub async fn execute_workflow(
+    workflow: &Workflow,
+    registry: &NodeRegistry,
+    options: &ExecutionOptions,
+) -> RunResult {
+    execute_workflow_seeded(workflow, registry, options, HashMap::new()).await
+}
+
+/// 与 [`execute_workflow`] 相同，但 `seeds` 中的节点视为已经执行完成：
+/// 不再执行，其输出从激活的输出传给子节点，并出现在返回结果的 `outputs` 中（执行记录标记为 `reused`）
+pub async fn execute_workflow_seeded(
+    workflow: &Workflow,
+    registry: &NodeRegistry,
+    options: &ExecutionOptions,
+    seeds: HashMap<String, NodeOutput>,
+) -> RunResult {
+    let mut session = begin_run(workflow, options).await;
+    let static_data = begin_static_data(workflow, session.as_ref()).await;
+    let mut result = run_scheduler(workflow, registry, options, seeds, &mut session, &static_data).await;
+    end_static_data(workflow, session.as_ref(), &static_data, &mut result).await;
+    end_run(options, session, result).await
+}
+
+/// 与 [`execute_workflow_seeded`] 相同，但继续写入已有的执行记录（恢复暂停的执行时使用）
+pub(crate) async fn execute_in_session(
+    workflow: &Workflow,
+    registry: &NodeRegistry,
+    options: &ExecutionOptions,
+    seeds: HashMap<String, NodeOutput>,
+    session: ExecutionSession,
+) -> RunResult {
+    options.emit(ExecutionEvent::RunStarted { workflow_id: workflow.id.clone() });
+    let static_data = begin_static_data(workflow, Some(&session)).await;
+    let mut session = Some(session);
+    let mut result = run_scheduler(workflow, registry, options, seeds, &mut session, &static_data).await;
+    end_static_data(workflow, session.as_ref(), &static_data, &mut result).await;
+    end_run(options, session, result).await
+}
+
+/// 运行开始：发送事件并按需创建执行记录
+pub(crate) async fn begin_run(workflow: &Workflow, options: &ExecutionOptions) -> Option<ExecutionSession> {
+    options.emit(ExecutionEvent::RunStarted { workflow_id: workflow.id.clone() });
+    match &options.recorder {
+        Some(recorder) => recorder.start(workflow).await,
+        None => None,
+    }
+}
+
+/// 本次运行使用的静态数据：开启执行记录时以 workflows 表中保存的数据为准，否则使用工作流自带的数据
+async fn begin_static_data(workflow: &Workflow, session: Option<&ExecutionSession>) -> StaticDataStore {
+    let saved = match session {
+        Some(session) => session.load_static_data(workflow).await,
+        None => None,
+    };
+    StaticDataStore::new(saved.unwrap_or_else(|| workflow.static_data.clone()))
+}
+
+/// 静态数据在运行中被修改时（无论运行是否成功）放入运行结果，开启执行记录时写回 workflows 表
+async fn end_static_data(
+    workflow: &Workflow,
+    session: Option<&ExecutionSession>,
+    static_data: &StaticDataStore,
+    result: &mut RunResult,
+) {
+    if !static_data.is_changed() {
+        return;
+    }
+    let snapshot = static_data.snapshot();
+    if let Some(session) = session {
+        session.save_static_data(workflow, snapshot.clone()).await;
+    }
+    result.static_data = Some(snapshot);
+}
+
+/// 运行结束：完成执行记录并发送事件（运行暂停在 Wait 节点上时发送 RunWaiting）
+pub(crate) async fn end_run(
+    options: &ExecutionOptions,
+    session: Option<ExecutionSession>,
+    mut result: RunResult,
+) -> RunResult {
+    if let Some(session) = session {
+        result.execution_id = Some(session.id().to_string());
+        session.finish(&result).await;
+    }
+    let event = match &result.waiting {
+        Some(waiting) => ExecutionEvent::RunWaiting { nodes: waiting.nodes.clone(), wait_till: waiting.wait_till },
+        None => ExecutionEvent::RunFinished { error: result.error.clone() },
+    };
+    options.emit(event);
+    result
+}
+
+async fn run_scheduler(
+    workflow: &Workflow,
+    registry: &NodeRegistry,
+    options: &ExecutionOptions,
+    seeds: HashMap<String, NodeOutput>,
+    session: &mut Option<ExecutionSession>,
+    static_data: &StaticDataStore,
+) -> RunResult {
+    let mut node_runs: HashMap<String, NodeRunRecord> = seeds
+        .iter()
+        .map(|(node_id, seed)| {
+            let record = NodeRunRecord {
+                reused: true,
+                outputs: seed.active_outputs(),
+                ..Default::default()
+            };
+            (node_id.clone(), record)
+        })
+        .collect();
+    if let Some(session) = session.as_mut() {
+        session.nodes_reused(seeds.iter().map(|(node_id, seed)| (node_id, &seed.data, &node_runs[node_id])));
+    }
+    let run = RunContext::new(workflow, session.as_ref().map(|s| s.id()), Utc::now(), &options.env_vars);
+    let mut scheduler = Scheduler::new(workflow, seeds);
+    let mut in_flight: JoinSet<NodeTaskResult> = JoinSet::new();
+    let fail = |node_runs, node: Option<&str>, err: &NodeError, outputs| RunResult {
+        outputs,
+        node_runs,
+        error: Some(RunError::from_node_error(node, err)),
+        ..Default::default()
+    };
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+    // 暂停的 Wait 节点及其恢复时间
+    let mut paused: Vec<(String, DateTime<Utc>)> = Vec::new();
+
+    loop {
+        if options.cancel_token.is_cancelled() {
+            in_flight.abort_all();
+            let err = NodeError::Cancelled("Workflow run was cancelled".into());
+            return fail(node_runs, None, &err, scheduler.results);
+        }
+
+        // 1) 在并发上限内启动所有就绪节点
+        while in_flight.len() < options.max_parallelism {
+            let Some(ready) = scheduler.ready.pop_front() else {
+                break;
+            };
+            let node_id = ready.node_id.clone();
+            if workflow.is_loop_node(&node_id) {
+                let result = match scheduler.step_loop(ready, &run) {
+                    Ok(result) => result,
+                    Err(err) => {
+                        if !node_failed(&mut scheduler, options, session, &mut node_runs, &node_id, &err, Vec::new()) {
+                            in_flight.abort_all();
+                            return fail(node_runs, Some(&node_id), &err, scheduler.results);
+                        }
+                        continue;
+                    }
+                };
+                let record = NodeRunRecord {
+                    outputs: result.emissions.iter().map(|e| e.output.clone()).collect(),
+                    ..Default::default()
+                };
+                if let Some(session) = session.as_mut() {
+                    session.node_finished(&node_id, Some(&result.data), &record);
+                }
+                node_runs.insert(node_id.clone(), record);
+                options.emit(ExecutionEvent::NodeFinished {
+                    node: node_id.clone(),
+                    summary: result.summary(),
+                    pinned: false,
+                });
+                scheduler.complete(&node_id, result);
+                continue;
+            }
+            if let Some(pinned) = workflow.pin_data.get(&node_id).filter(|_| options.use_pin_data) {
+                debug!("Using pinned data for node '{}'", node_id);
+                let record = NodeRunRecord {
+                    pinned: true,
+                    outputs: vec![MAIN_OUTPUT.to_string()],
+                    ..Default::default()
+                };
+                if let Some(session) = session.as_mut() {
+                    session.node_finished(&node_id, Some(pinned), &record);
+                }
+                node_runs.insert(node_id.clone(), record);
+                let result = main_result(pinned.clone());
+                options.emit(ExecutionEvent::NodeFinished {
+                    node: node_id.clone(),
+                    summary: result.summary(),
+                    pinned: true,
+                });
+                scheduler.complete(&node_id, result);
+                continue;
+            }
+            let prepared = prepare_node(workflow, registry, options, &run, static_data, &scheduler.results, ready)
+                .and_then(|(node_impl, exec_ctx)| {
+                    let node_cfg = &workflow.nodes[&node_id];
+                    let wait_till = pause_until(node_cfg, &exec_ctx.parameters, session.is_some(), Utc::now())?;
+                    let item_parameters = item_parameters(&node_id, node_cfg, &exec_ctx.items, &scheduler.results, &run)?;
+                    Ok((node_impl, exec_ctx, wait_till, item_parameters))
+                });
+            let (node_impl, exec_ctx, wait_till, item_parameters) = match prepared {
+                Ok(prepared) => prepared,
+                Err(err) => {
+                    if !node_failed(&mut scheduler, options, session, &mut node_runs, &node_id, &err, Vec::new()) {
+                        in_flight.abort_all();
+                        return fail(node_runs, Some(&node_id), &err, scheduler.results);
+                    }
+                    continue;
+                }
+            };
+            if let (Some(wait_till), Some(session)) = (wait_till, session.as_mut()) {
+                debug!("Node '{}' pauses the run until {}", node_id, wait_till);
+                session.node_started(&node_id, &exec_ctx.input_data);
+                options.emit(ExecutionEvent::NodeWaiting { node: node_id.clone(), wait_till });
+                paused.push((node_id, wait_till));
+                continue;
+            }
+            let node_cfg = &workflow.nodes[&node_id];
+            let settings = NodeRunSettings {
+                retry_policy: node_cfg.retry_policy.clone(),
+                execution_mode: node_cfg.execution_mode,
+                timeout: node_cfg.timeout_ms.map(Duration::from_millis),
+                events: options.events.clone(),
+                item_parameters,
+            };
+            debug!("Starting node '{}'", node_id);
+            options.emit(ExecutionEvent::NodeStarted { node: node_id.clone() });
+            if let Some(session) = session.as_mut() {
+                session.node_started(&node_id, &exec_ctx.input_data);
+            }
+            in_flight.spawn(async move {
+                let (result, attempts) =
+                    run_node(&node_id, node_impl.as_ref(), exec_ctx, &settings).await;
+                (node_id, result, attempts)
+            });
+        }
+
+        // 2) 等待任意一个节点结束，同时响应取消和运行超时
+        let joined = tokio::select! {
+            joined = in_flight.join_next() => joined,
+            _ = options.cancel_token.cancelled() => continue,
+            _ = sleep_until_deadline(deadline) => {
+                in_flight.abort_all();
+                let err = NodeError::Timeout(format!(
+                    "Workflow run exceeded {} ms",
+                    options.timeout.unwrap_or_default().as_millis()
+                ));
+                error!("{}", err);
+                return fail(node_runs, None, &err, scheduler.results);
+            }
+        };
+        let Some(joined) = joined else {
+            break;
+        };
+        let (node_id, result, attempts) = match joined {
+            Ok(task_result) => task_result,
+            Err(e) => {
+                let err = NodeError::ExecutionFailed(format!("Node task failed to complete: {e}"));
+                in_flight.abort_all();
+                return fail(node_runs, None, &err, scheduler.results);
+            }
+        };
+        let err = match result {
+            Ok(node_result) => {
+                let record = NodeRunRecord {
+                    attempts,
+                    outputs: node_result.emissions.iter().map(|e| e.output.clone()).collect(),
+                    ..Default::default()
+                };
+                if let Some(session) = session.as_mut() {
+                    session.node_finished(&node_id, Some(&node_result.data), &record);
+                }
+                node_runs.insert(node_id.clone(), record);
+                options.emit(ExecutionEvent::NodeFinished {
+                    node: node_id.clone(),
+                    summary: node_result.summary(),
+                    pinned: false,
+                });
+                scheduler.complete(&node_id, node_result);
+                continue;
+            }
+            Err(err) => err,
+        };
+
+        // 3) 按节点的 on_error 配置处理失败
+        if !node_failed(&mut scheduler, options, session, &mut node_runs, &node_id, &err, attempts) {
+            in_flight.abort_all();
+            return fail(node_runs, Some(&node_id), &err, scheduler.results);
+        }
+    }
+
+    let waiting = paused.iter().map(|(_, wait_till)| *wait_till).max().map(|wait_till| WaitingState {
+        nodes: paused.into_iter().map(|(node_id, _)| node_id).collect(),
+        wait_till,
+    });
+    RunResult {
+        outputs: scheduler.results,
+        node_runs,
+        waiting,
+        ..Default::default()
+    }
+}
+
+/// 按节点的 on_error 配置处理失败（执行失败，或映射、参数表达式等在执行前就出错）：
+/// 记录失败并发送事件；`StopWorkflow` 时返回 false，由调用方结束运行，
+/// 其余方式把 `{ "error": ... }` 从对应的输出传出并返回 true
+fn node_failed(
+    scheduler: &mut Scheduler<'_>,
+    options: &ExecutionOptions,
+    session: &mut Option<ExecutionSession>,
+    node_runs: &mut HashMap<String, NodeRunRecord>,
+    node_id: &str,
+    err: &NodeError,
+    attempts: Vec<NodeAttempt>,
+) -> bool {
+    let run_error = RunError::from_node_error(Some(node_id), err);
+    options.emit(ExecutionEvent::NodeFailed { node: node_id.to_string(), error: run_error.clone() });
+    let on_error = scheduler.workflow.nodes.get(node_id).map(|n| n.on_error).unwrap_or_default();
+    let continue_output = match on_error {
+        OnError::StopWorkflow => None,
+        OnError::ContinueRegularOutput => Some(MAIN_OUTPUT),
+        OnError::ContinueErrorOutput => Some(ERROR_OUTPUT),
+    };
+    let record = NodeRunRecord {
+        attempts,
+        error: Some(run_error.clone()),
+        outputs: continue_output.into_iter().map(String::from).collect(),
+        ..Default::default()
+    };
+    if let Some(session) = session.as_mut() {
+        session.node_finished(node_id, None, &record);
+    }
+    node_runs.insert(node_id.to_string(), record);
+    let Some(output_name) = continue_output else {
+        error!("Execution error at node '{}': {:?}", node_id, err);
+        return false;
+    };
+    warn!(
+        "Node '{}' failed, continuing on output '{}': {}",
+        node_id, output_name, err
+    );
+    let data = json!({ "error": run_error });
+    let emission = Emission {
+        output: output_name.to_string(),
+        items: NodeItem::from_value(&data),
+        data: data.clone(),
+    };
+    scheduler.complete(node_id, NodeResult { data, emissions: vec![emission] });
+    true
+}
+
+/// 等待到截止时间，没有截止时间时永远等待
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 只从默认输出传出数据的结果
+fn main_result(data: Value) -> NodeResult {
+    let emission = Emission {
+        output: MAIN_OUTPUT.to_string(),
+        items: NodeItem::from_value(&data),
+        data: data.clone(),
+    };
+    NodeResult { data, emissions: vec![emission] }
+}
+
+/// 按节点的执行方式运行节点：
+///
+/// - `RunOnceForAllItems`：以全部输入执行一次，没有配对信息的输出数据项会自动与输入配对；
+/// - `RunOnceForEachItem`：对每个输入数据项分别执行（`input_data` 为该数据项的 JSON），
+///   输出数据项按输入顺序拼接并与产生它的输入数据项配对，同一输出的数据合并为数组传出。
+async fn run_node(
+    node_id: &str,
+    node_impl: &dyn NodeType,
+    exec_ctx: NodeExecutionContext,
+    settings: &NodeRunSettings,
+) -> (Result<NodeResult, NodeError>, Vec<NodeAttempt>) {
+    match settings.execution_mode {
+        ItemExecutionMode::RunOnceForAllItems => {
+            let (result, attempts) =
+                execute_with_retry(node_id, node_impl, &exec_ctx, settings, None).await;
+            let result = result.map(|output| {
+                let mut items = output.output_items();
+                NodeItem::pair_with_inputs(&mut items, exec_ctx.items.len());
+                let emissions = output
+                    .active_outputs()
+                    .into_iter()
+                    .map(|name| Emission {
+                        output: name,
+                        data: output.data.clone(),
+                        items: items.clone(),
+                    })
+                    .collect();
+                NodeResult { data: output.data, emissions }
+            });
+            (result, attempts)
+        }
+        ItemExecutionMode::RunOnceForEachItem => {
+            let mut attempts = Vec::new();
+            let mut all_items = Vec::new();
+            let mut by_output: Vec<(String, Vec<NodeItem>)> = Vec::new();
+            for (index, item) in exec_ctx.items.iter().enumerate() {
+                let parameters = settings.item_parameters.get(index).unwrap_or(&exec_ctx.parameters);
+                let item_ctx = NodeExecutionContext {
+                    parameters: parameters.clone(),
+                    input_data: item.json.clone(),
+                    globals: exec_ctx.globals.clone(),
+                    env: exec_ctx.env.clone(),
+                    pin_data: exec_ctx.pin_data.clone(),
+                    inputs: vec![item.json.clone()],
+                    items: vec![item.clone()],
+                    workflows: exec_ctx.workflows.clone(),
+                    expressions: exec_ctx.expressions.clone(),
+                    static_data: exec_ctx.static_data.clone(),
+                };
+                let (result, item_attempts) =
+                    execute_with_retry(node_id, node_impl, &item_ctx, settings, Some(index)).await;
+                attempts.extend(item_attempts);
+                let output = match result {
+                    Ok(output) => output,
+                    Err(err) => return (Err(err), attempts),
+                };
+
+                let mut items = output.output_items();
+                for out in items.iter_mut() {
+                    out.paired_item = Some(PairedItem::new(index));
+                }
+                for name in output.active_outputs() {
+                    match by_output.iter_mut().find(|(o, _)| *o == name) {
+                        Some((_, list)) => list.extend(items.iter().cloned()),
+                        None => by_output.push((name, items.clone())),
+                    }
+                }
+                all_items.extend(items);
+            }
+
+            let emissions = by_output
+                .into_iter()
+                .map(|(output, items)| Emission {
+                    output,
+                    data: NodeItem::to_value(&items),
+                    items,
+                })
+                .collect();
+            let result = NodeResult {
+                data: NodeItem::to_value(&all_items),
+                emissions,
+            };
+            (Ok(result), attempts)
+        }
+    }
+}
+
+/// 执行单个节点，失败时按重试策略等待后重新执行，返回最终结果和每次尝试的记录
+///
+/// 设置了超时时间时，单次执行超时会被中止并视为一次 `Timeout` 错误。
+async fn execute_with_retry(
+    node_id: &str,
+    node_impl: &dyn NodeType,
+    exec_ctx: &NodeExecutionContext,
+    settings: &NodeRunSettings,
+    item: Option<usize>,
+) -> (Result<NodeOutput, NodeError>, Vec<NodeAttempt>) {
+    let mut attempts = Vec::new();
+    let mut attempt = 1;
+    loop {
+        let started_at = Utc::now();
+        let result = match settings.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, node_impl.execute(exec_ctx))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(NodeError::Timeout(format!(
+                        "Node {} exceeded {} ms",
+                        node_id,
+                        timeout.as_millis()
+                    )))
+                }),
+            None => node_impl.execute(exec_ctx).await,
+        };
+        attempts.push(NodeAttempt {
+            attempt,
+            item,
+            started_at,
+            finished_at: Utc::now(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+
+        let err = match &result {
+            Err(err) => err,
+            Ok(_) => return (result, attempts),
+        };
+        let Some(policy) = settings.retry_policy.as_ref().filter(|p| p.should_retry(attempt, err)) else {
+            return (result, attempts);
+        };
+        let delay = policy.delay_for_attempt(attempt);
+        warn!(
+            "Node '{}' failed on attempt {}/{}: {}; retrying in {:?}",
+            node_id, attempt, policy.max_attempts, err, delay
+        );
+        emit(settings.events.as_ref(), ExecutionEvent::NodeRetrying {
+            node: node_id.to_string(),
+            next_attempt: attempt + 1,
+            delay_ms: delay.as_millis() as u64,
+            error: err.to_string(),
+        });
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// 合并产生了输出的父节点输出：只有一个时直接使用；多个时合并为数组；没有父节点时使用空对象
+fn merge_inputs(inputs: &[Option<Value>]) -> Value {
+    let received: Vec<&Value> = inputs.iter().flatten().collect();
+    match received.as_slice() {
+        [] => json!({}),
+        [single] => (*single).clone(),
+        _ => Value::Array(received.into_iter().cloned().collect()),
+    }
+}
+
+/// 查找节点实现并构造执行上下文
+///
+/// 配置了 input_mapping 时，数据项由映射结果拆分得到（父节点数据项上的二进制数据不再保留）；
+/// 没有父节点时使用由空对象构成的单个数据项。
+fn prepare_node(
+    workflow: &Workflow,
+    registry: &NodeRegistry,
+    options: &ExecutionOptions,
+    run: &RunContext,
+    static_data: &StaticDataStore,
+    results: &NodeResults,
+    ready: ReadyNode,
+) -> Result<(Arc<dyn NodeType>, NodeExecutionContext), NodeError> {
+    let ReadyNode { node_id, inputs, items } = ready;
+    let node_id = node_id.as_str();
+    let node_cfg = workflow.nodes.get(node_id).ok_or_else(|| {
+        NodeError::InvalidConfig(format!("Node '{}' not found in workflow", node_id))
+    })?;
+
+    // 使用 node_type_name 从注册表中查找节点实现
+    let node_impl = registry.get(&node_cfg.node_type_name).ok_or_else(|| {
+        let err_msg = format!(
+            "NodeType '{}' not registered for node '{}'",
+            node_cfg.node_type_name, node_id
+        );
+        error!("{}", err_msg);
+        NodeError::InvalidConfig(err_msg)
+    })?;
+
+    let merged_input = merge_inputs(&inputs);
+    let (input_data, items) = match &node_cfg.input_mapping {
+        Some(mapping) => {
+            let scope = ExpressionScope::new(&merged_input).with_nodes(results).with_run(run);
+            let mapped = apply_input_mapping(node_id, mapping, &scope)?;
+            let items = NodeItem::from_value(&mapped);
+            (mapped, items)
+        }
+        None if inputs.is_empty() => {
+            let items = NodeItem::from_value(&merged_input);
+            (merged_input, items)
+        }
+        None => (merged_input, items),
+    };
+
+    // 此处使用 custom_config 作为节点执行参数，其中的表达式以输入数据、已执行节点的输出和运行上下文求值
+    let parameters = match &node_cfg.custom_config {
+        Some(config) => {
+            let scope = ExpressionScope::new(&input_data).with_nodes(results).with_run(run);
+            render_parameters(node_cfg, config, &scope).map_err(|e| parameter_error(node_id, e))?
+        }
+        None => Value::Null,
+    };
+    let expressions = NodeExpressions::new(&parameters, results, run);
+    let exec_ctx = NodeExecutionContext {
+        parameters,
+        input_data,
+        globals: run.globals.clone(),
+        env: run.env.clone(),
+        pin_data: workflow.pin_data.get(node_id).cloned(),
+        inputs: inputs.into_iter().map(Option::unwrap_or_default).collect(),
+        items,
+        workflows: options.workflows.clone(),
+        expressions: Some(Arc::new(expressions)),
+        static_data: Some(static_data.for_node(node_id)),
+    };
+    Ok((node_impl, exec_ctx))
+}
+
+/// 逐项执行的节点：以各输入数据项分别解析参数中的表达式
+fn item_parameters(
+    node_id: &str,
+    node_cfg: &Node,
+    items: &[NodeItem],
+    results: &NodeResults,
+    run: &RunContext,
+) -> Result<Vec<Value>, NodeError> {
+    match (node_cfg.execution_mode, &node_cfg.custom_config) {
+        (ItemExecutionMode::RunOnceForEachItem, Some(config)) => {
+            let scope = ExpressionScope::new(&Value::Null).with_nodes(results).with_run(run);
+            items
+                .iter()
+                .map(|item| render_parameters(node_cfg, config, &scope.with_json(&item.json)))
+                .collect::<Result<_, _>>()
+                .map_err(|e| parameter_error(node_id, e))
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// 解析节点参数中的表达式。Execute Workflow 节点内联的子工作流定义（`workflow` 参数）原样保留，
+/// 其中的表达式在子工作流运行时以子工作流的数据求值
+fn render_parameters(
+    node_cfg: &Node,
+    config: &Value,
+    scope: &ExpressionScope<'_>,
+) -> Result<Value, expression::ExpressionError> {
+    match config {
+        Value::Object(params) if node_cfg.node_type_name == EXECUTE_WORKFLOW_NODE_TYPE => params
+            .iter()
+            .map(|(key, value)| match key.as_str() {
+                "workflow" => Ok((key.clone(), value.clone())),
+                _ => expression::render(value, scope).map(|rendered| (key.clone(), rendered)),
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        _ => expression::render(config, scope),
+    }
+}
+
+fn parameter_error(node_id: &str, err: expression::ExpressionError) -> NodeError {
+    let err_msg = format!("Expression error in parameters of node '{}': {}", node_id, err);
+    error!("{}", err_msg);
+    NodeError::InvalidConfig(err_msg)
+}
+
+/// 对合并后的上游数据执行 input_mapping，映射表达式是 JMESPath（`@` 指向上游数据，见 [`crate::expression`]）
+/// 或 jq 过滤器（见 [`crate::jq`]）
+pub fn apply_input_mapping(
+    node_id: &str,
+    mapping: &InputMapping,
+    scope: &ExpressionScope<'_>,
+) -> Result<Value, NodeError> {
+    match mapping {
+        InputMapping::Single(expr_str) => expression::evaluate(expr_str, scope).map_err(|e| {
+            let err_msg = format!(
+                "Mapping error at node '{}' (expr='{:?}'): {}",
+                node_id, expr_str, e
+            );
+            error!("{}", err_msg);
+            NodeError::InvalidConfig(err_msg)
+        }),
+        InputMapping::Jq { jq } => jq::evaluate(jq, scope).map_err(|e| {
+            let err_msg = format!("Mapping error at node '{}' (jq='{:?}'): {}", node_id, jq, e);
+            error!("{}", err_msg);
+            NodeError::InvalidConfig(err_msg)
+        }),
+        InputMapping::Multi { fields, .. } => {
+            let mut mapped_obj = serde_json::Map::new();
+            for (field, expr_str) in fields {
+                let mapped_field = expression::evaluate(expr_str, scope).map_err(|e| {
+                    let err_msg = format!(
+                        "Mapping error at node '{}' for field '{}' (expr='{:?}'): {}",
+                        node_id, field, expr_str, e
+                    );
+                    error!("{}", err_msg);
+                    NodeError::InvalidConfig(err_msg)
+                })?;
+                mapped_obj.insert(field.clone(), mapped_field);
+            }
+            Ok(Value::Object(mapped_obj))
+        }
+    }
+}
+
+/// 去除重复项并保持原有顺序（同一对节点可能被重复连接）
+fn unique(ids: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    ids.into_iter().filter(|id| seen.insert(id.clone())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alphaflow_nodes::condition::if_handler::{IfHandler, IF_FALSE_OUTPUT, IF_TRUE_OUTPUT};
+    use alphaflow_nodes::condition::switch_handler::SwitchHandler;
+    use alphaflow_nodes::merge::merge_handler::MergeHandler;
+    use alphaflow_nodes::condition::{IF_NODE_TYPE, SWITCH_NODE_TYPE};
+    use alphaflow_nodes::merge::MERGE_NODE_TYPE;
+    use alphaflow_nodes::split_in_batches::split_in_batches_handler::SplitInBatchesHandler;
+    use alphaflow_nodes::split_in_batches::SPLIT_IN_BATCHES_NODE_TYPE;
+    use alphaflow_nodes::item::BinaryData;
+    use alphaflow_nodes::node::{Backoff, Node, OnError};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use alphaflow_nodes::node_type::NodeErrorKind;
+    use crate::events::event_channel;
+    use std::time::Instant;
+
+    /// 测试用节点：等待 `delay_ms` 毫秒后返回 { "node": <参数中的 tag>, "input": <输入> }
+    struct DelayNode;
+
+    #[async_trait]
+    impl NodeType for DelayNode {
+        fn name(&self) -> &str {
+            "delay"
+        }
+        fn display_name(&self) -> &str {
+            "Delay Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            let delay = ctx.parameters["delay_ms"].as_u64().unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            if ctx.parameters["fail"].as_bool().unwrap_or(false) {
+                return Err(NodeError::ExecutionFailed("boom".into()));
+            }
+            Ok(NodeOutput {
+                data: json!({ "node": ctx.parameters["tag"], "input": ctx.input_data }),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// 测试用节点：记录被执行的次数，返回输入数据
+    struct CountingNode(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl NodeType for CountingNode {
+        fn name(&self) -> &str {
+            "counting"
+        }
+        fn display_name(&self) -> &str {
+            "Counting Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(NodeOutput { data: ctx.input_data.clone(), ..Default::default() })
+        }
+    }
+
+    /// 测试用节点：前 `fail_times` 次执行返回 `kind` 类别的错误，之后返回执行次数
+    struct FlakyNode(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl NodeType for FlakyNode {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+        fn display_name(&self) -> &str {
+            "Flaky Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            let fail_times = ctx.parameters["fail_times"].as_u64().unwrap_or(0) as usize;
+            if calls <= fail_times {
+                return Err(match ctx.parameters["kind"].as_str() {
+                    Some("invalid_config") => NodeError::InvalidConfig("bad config".into()),
+                    _ => NodeError::ExecutionFailed(format!("transient failure #{calls}")),
+                });
+            }
+            Ok(NodeOutput { data: json!({ "calls": calls }), ..Default::default() })
+        }
+    }
+
+    /// 测试用节点：以序列化后的输入数据项作为输出
+    struct ItemsNode;
+
+    #[async_trait]
+    impl NodeType for ItemsNode {
+        fn name(&self) -> &str {
+            "items"
+        }
+        fn display_name(&self) -> &str {
+            "Items Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            Ok(NodeOutput::new(serde_json::to_value(&ctx.items).unwrap()))
+        }
+    }
+
+    /// 测试用节点：输出一个带二进制数据的数据项
+    struct BinaryNode;
+
+    #[async_trait]
+    impl NodeType for BinaryNode {
+        fn name(&self) -> &str {
+            "binary"
+        }
+        fn display_name(&self) -> &str {
+            "Binary Node"
+        }
+        async fn execute(&self, _ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            let item = NodeItem::new(json!({ "file": "hello.txt" })).with_binary("file", BinaryData {
+                data: "aGVsbG8=".into(),
+                mime_type: "text/plain".into(),
+                file_name: Some("hello.txt".into()),
+            });
+            Ok(NodeOutput::from_items(vec![item]))
+        }
+    }
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(DelayNode));
+        registry
+    }
+
+    fn flaky_registry(counter: Arc<AtomicUsize>) -> NodeRegistry {
+        let mut registry = registry();
+        registry.register(Arc::new(FlakyNode(counter)));
+        registry
+    }
+
+    fn flaky_node(config: Value, policy: RetryPolicy) -> Node {
+        Node::new("flaky", "flaky")
+            .with_custom_config(config)
+            .with_retry_policy(policy)
+    }
+
+    fn counting_registry(counter: Arc<AtomicUsize>) -> NodeRegistry {
+        let mut registry = registry();
+        registry.register(Arc::new(CountingNode(counter)));
+        registry
+    }
+
+    /// a -> [b, c, d] -> join，三个分支的耗时分别为 0ms / 100ms / 300ms
+    fn three_branch_workflow(join_mode: JoinMode) -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("a", 0));
+        wf.add_node(delay_node("b", 0));
+        wf.add_node(delay_node("c", 100));
+        wf.add_node(delay_node("d", 300));
+        wf.add_node(Node::new("join", "counting").with_join_mode(join_mode));
+        for branch in ["b", "c", "d"] {
+            wf.connect_nodes("a", branch).unwrap();
+            wf.connect_nodes(branch, "join").unwrap();
+        }
+        wf
+    }
+
+    fn branch_tags(input: &Value) -> Vec<Value> {
+        match input {
+            Value::Array(items) => items.iter().map(|v| v["node"].clone()).collect(),
+            other => vec![other["node"].clone()],
+        }
+    }
+
+    fn delay_node(name: &str, delay_ms: u64) -> Node {
+        Node::new(name, "delay").with_custom_config(json!({ "tag": name, "delay_ms": delay_ms }))
+    }
+
+    /// start -> [b0..b4] -> join：5 个 200ms 的分支应当并发执行
+    fn fan_out_workflow() -> Workflow {
+        let mut wf = Workflow::new(Some("fan_out".into()));
+        wf.add_node(delay_node("start", 0));
+        wf.add_node(delay_node("join", 0));
+        for i in 0..5 {
+            let name = format!("b{i}");
+            wf.add_node(delay_node(&name, 200));
+            wf.connect_nodes("start", &name).unwrap();
+            wf.connect_nodes(&name, "join").unwrap();
+        }
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_independent_branches_run_concurrently() {
+        let wf = fan_out_workflow();
+        let begin = Instant::now();
+        let results = wf.run(&registry()).await.expect("workflow should succeed");
+        let elapsed = begin.elapsed();
+
+        assert_eq!(results.len(), 7);
+        assert!(elapsed < Duration::from_millis(600), "branches should overlap, took {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_max_parallelism_limits_concurrency() {
+        let wf = fan_out_workflow();
+        let options = ExecutionOptions::default().with_max_parallelism(1);
+        let begin = Instant::now();
+        let results = wf.run_with_options(&registry(), &options).await.unwrap();
+        let elapsed = begin.elapsed();
+
+        assert_eq!(results.len(), 7);
+        assert!(elapsed >= Duration::from_millis(1000), "branches should run one by one, took {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_join_input_follows_connection_order() {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("slow", 150));
+        wf.add_node(delay_node("fast", 0));
+        wf.add_node(delay_node("join", 0));
+        wf.connect_nodes("slow", "join").unwrap();
+        wf.connect_nodes("fast", "join").unwrap();
+
+        let results = wf.run(&registry()).await.unwrap();
+        // 虽然 fast 先完成，合并结果仍按连接顺序排列
+        let tags: Vec<&Value> = results["join"]["input"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| &v["node"])
+            .collect();
+        assert_eq!(tags, vec![&json!("slow"), &json!("fast")]);
+    }
+
+    #[tokio::test]
+    async fn test_failure_stops_workflow() {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("a", 0).with_custom_config(json!({ "tag": "a", "fail": true })));
+        wf.add_node(delay_node("b", 0));
+        wf.connect_nodes("a", "b").unwrap();
+
+        let err = wf.run(&registry()).await.unwrap_err();
+        assert!(matches!(err, NodeError::ExecutionFailed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_diamond_join_executes_once_with_all_inputs() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("a", 0));
+        wf.add_node(delay_node("b", 50));
+        wf.add_node(delay_node("c", 0));
+        wf.add_node(Node::new("d", "counting"));
+        wf.connect_nodes("a", "b").unwrap();
+        wf.connect_nodes("a", "c").unwrap();
+        wf.connect_nodes("b", "d").unwrap();
+        wf.connect_nodes("c", "d").unwrap();
+
+        let results = wf.run(&counting_registry(counter.clone())).await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1, "join node should run exactly once");
+        assert_eq!(branch_tags(&results["d"]), vec![json!("b"), json!("c")]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_any_uses_first_finished_parent() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let wf = three_branch_workflow(JoinMode::WaitAny);
+
+        let results = wf.run(&counting_registry(counter.clone())).await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(branch_tags(&results["join"]), vec![json!("b")]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_n_uses_first_n_parents() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let wf = three_branch_workflow(JoinMode::WaitN(2));
+
+        let results = wf.run(&counting_registry(counter.clone())).await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(branch_tags(&results["join"]), vec![json!("b"), json!("c")]);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_parent_does_not_block_join() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = three_branch_workflow(JoinMode::WaitAll);
+        wf.nodes.get_mut("d").unwrap().disabled = true;
+
+        let results = wf.run(&counting_registry(counter.clone())).await.unwrap();
+        assert!(!results.contains_key("d"));
+        assert_eq!(branch_tags(&results["join"]), vec![json!("b"), json!("c")]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success_records_attempts() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        let policy = RetryPolicy::new(3).with_backoff(Backoff::Exponential {
+            initial_delay_ms: 20,
+            factor: 2,
+            max_delay_ms: 100,
+        });
+        wf.add_node(flaky_node(json!({ "fail_times": 2 }), policy));
+
+        let started = Instant::now();
+        let result = wf.execute(&flaky_registry(counter.clone())).await;
+        assert!(result.is_success());
+        assert!(started.elapsed() >= Duration::from_millis(60), "should back off 20ms + 40ms");
+        assert_eq!(result.outputs["flaky"], json!({ "calls": 3 }));
+
+        let attempts = &result.node_runs["flaky"].attempts;
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].error.as_deref(), Some("Execution failed: transient failure #1"));
+        assert_eq!(attempts[2].attempt, 3);
+        assert!(attempts[2].error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausted_reports_error_with_attempts() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        let policy = RetryPolicy::new(2).with_backoff(Backoff::Fixed { delay_ms: 0 });
+        wf.add_node(flaky_node(json!({ "fail_times": 5 }), policy));
+
+        let result = wf.execute(&flaky_registry(counter.clone())).await;
+        let error = result.error.as_ref().unwrap();
+        assert_eq!(error.node.as_deref(), Some("flaky"));
+        assert_eq!(error.message, "transient failure #2");
+        assert_eq!(result.node_runs["flaky"].attempts.len(), 2);
+        assert!(matches!(result.into_outputs(), Err(NodeError::ExecutionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_error_kinds_not_in_retry_on() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        let policy = RetryPolicy::new(3).with_backoff(Backoff::Fixed { delay_ms: 0 });
+        wf.add_node(flaky_node(json!({ "fail_times": 1, "kind": "invalid_config" }), policy));
+
+        let err = wf.run(&flaky_registry(counter.clone())).await.unwrap_err();
+        assert!(matches!(err, NodeError::InvalidConfig(_)));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    /// a(失败) -> ok，a:error -> handler
+    fn failing_workflow(on_error: OnError) -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("start", 0));
+        wf.add_node(
+            delay_node("a", 0)
+                .with_custom_config(json!({ "tag": "a", "fail": true }))
+                .with_on_error(on_error),
+        );
+        wf.add_node(delay_node("ok", 0));
+        wf.add_node(delay_node("handler", 0));
+        wf.connect_nodes("start", "a").unwrap();
+        wf.connect_nodes("a", "ok").unwrap();
+        wf.connect_nodes_on_output("a", ERROR_OUTPUT, "handler").unwrap();
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_stop_workflow_returns_partial_results_with_error() {
+        let result = failing_workflow(OnError::StopWorkflow).execute(&registry()).await;
+        let error = result.error.as_ref().unwrap();
+        assert_eq!(error.node.as_deref(), Some("a"));
+        assert_eq!(error.message, "boom");
+        assert!(result.outputs.contains_key("start"));
+        assert!(!result.outputs.contains_key("ok"));
+        assert_eq!(result.node_runs["a"].error.as_ref(), Some(error));
+    }
+
+    #[tokio::test]
+    async fn test_mapping_error_follows_on_error() {
+        let mut wf = failing_workflow(OnError::ContinueErrorOutput);
+        let node = wf.nodes.get_mut("a").unwrap();
+        node.custom_config = Some(json!({ "tag": "a" }));
+        node.input_mapping = Some(InputMapping::Single("foo[".into()));
+
+        let result = wf.execute(&registry()).await;
+        assert!(result.is_success(), "{:?}", result.error);
+        assert_eq!(result.outputs["handler"]["input"]["error"]["node"], json!("a"));
+        assert_eq!(result.outputs["handler"]["input"]["error"]["kind"], json!("invalid_config"));
+        assert!(!result.outputs.contains_key("ok"));
+        assert!(result.node_runs["a"].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_continue_regular_output_passes_error_item_downstream() {
+        let results = failing_workflow(OnError::ContinueRegularOutput)
+            .run(&registry())
+            .await
+            .unwrap();
+        assert_eq!(results["a"]["error"]["message"], json!("boom"));
+        assert_eq!(results["a"]["error"]["kind"], json!("execution_failed"));
+        assert_eq!(results["ok"]["input"], results["a"]);
+        assert!(!results.contains_key("handler"));
+    }
+
+    #[tokio::test]
+    async fn test_continue_error_output_routes_to_error_branch() {
+        let result = failing_workflow(OnError::ContinueErrorOutput).execute(&registry()).await;
+        assert!(result.is_success());
+        assert_eq!(result.outputs["handler"]["input"]["error"]["node"], json!("a"));
+        assert!(!result.outputs.contains_key("ok"));
+        assert!(result.node_runs["a"].error.is_some());
+    }
+
+    /// check -(true)-> yes -> after, check -(false)-> no，两条分支在 join 汇合
+    fn if_workflow(status: u64) -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(
+            Node::new("start", "counting")
+                .with_input_mapping(InputMapping::Single(format!("`{{\"status\": {status}}}`"))),
+        );
+        wf.add_node(
+            Node::new("check", IF_NODE_TYPE).with_custom_config(json!({ "condition": "status == `200`" })),
+        );
+        wf.add_node(delay_node("yes", 0));
+        wf.add_node(delay_node("after_yes", 0));
+        wf.add_node(delay_node("no", 0));
+        wf.add_node(Node::new("join", "counting"));
+        wf.connect_nodes("start", "check").unwrap();
+        wf.connect_nodes_on_output("check", IF_TRUE_OUTPUT, "yes").unwrap();
+        wf.connect_nodes_on_output("check", IF_FALSE_OUTPUT, "no").unwrap();
+        wf.connect_nodes("yes", "after_yes").unwrap();
+        wf.connect_nodes("after_yes", "join").unwrap();
+        wf.connect_nodes("no", "join").unwrap();
+        wf
+    }
+
+    fn if_registry(counter: Arc<AtomicUsize>) -> NodeRegistry {
+        let mut registry = counting_registry(counter);
+        registry.register(Arc::new(IfHandler::new()));
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_if_node_skips_untaken_branch_and_descendants() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let results = if_workflow(200).run(&if_registry(counter.clone())).await.unwrap();
+        assert!(results.contains_key("yes"));
+        assert!(results.contains_key("after_yes"));
+        assert!(!results.contains_key("no"));
+        assert_eq!(results["join"]["node"], json!("after_yes"));
+
+        let results = if_workflow(500).run(&if_registry(counter.clone())).await.unwrap();
+        assert!(!results.contains_key("yes"));
+        assert!(!results.contains_key("after_yes"));
+        assert_eq!(results["no"]["input"], json!({ "status": 500 }));
+        assert_eq!(results["join"]["node"], json!("no"));
+    }
+
+    #[tokio::test]
+    async fn test_if_condition_uses_expression_variables() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = if_workflow(200);
+        wf.id = Some("wf-if".into());
+        let condition = "$globals.workflow_id == 'wf-if' && $node.start.json.status == `200`";
+        wf.nodes.get_mut("check").unwrap().custom_config = Some(json!({ "condition": condition }));
+        let results = wf.run(&if_registry(counter.clone())).await.unwrap();
+        assert!(results.contains_key("yes"));
+        assert!(!results.contains_key("no"));
+
+        wf.nodes.get_mut("check").unwrap().custom_config = Some(json!({ "condition": "status == `200`\n  && (" }));
+        let err = wf.run(&if_registry(counter)).await.unwrap_err();
+        assert!(err.to_string().contains("line 2, column"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_node_without_active_outputs_skips_all_children() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut registry = counting_registry(counter);
+        registry.register(Arc::new(SwitchHandler::new()));
+
+        let mut wf = Workflow::new(None);
+        wf.add_node(Node::new("route", SWITCH_NODE_TYPE).with_custom_config(json!({
+            "rules": [{ "output": "never", "condition": "`false`" }]
+        })));
+        wf.add_node(delay_node("a", 0));
+        wf.add_node(delay_node("b", 0));
+        wf.connect_nodes_on_output("route", "never", "a").unwrap();
+        wf.connect_nodes("route", "b").unwrap();
+
+        let results = wf.run(&registry).await.unwrap();
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["route"]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_node_receives_inputs_per_parent() {
+        let mut registry = registry();
+        registry.register(Arc::new(MergeHandler::new()));
+
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("slow", 100));
+        wf.add_node(delay_node("fast", 0));
+        wf.add_node(
+            Node::new("merge", MERGE_NODE_TYPE)
+                .with_custom_config(json!({ "mode": "merge_by_position" })),
+        );
+        wf.connect_nodes("slow", "merge").unwrap();
+        wf.connect_nodes("fast", "merge").unwrap();
+
+        let results = wf.run(&registry).await.unwrap();
+        // 两个父节点的输出按连接顺序合并，后连接的 fast 覆盖同名字段
+        assert_eq!(results["merge"], json!([{ "node": "fast", "input": {} }]));
+    }
+
+    /// check 为假时 merge 的第一个父节点 left 被跳过，第二个父节点 right 照常执行
+    fn skipped_merge_workflow(status: u64, mode: &str) -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(
+            Node::new("start", "counting")
+                .with_input_mapping(InputMapping::Single(format!("`{{\"id\": 1, \"status\": {status}}}`"))),
+        );
+        wf.add_node(Node::new("check", IF_NODE_TYPE).with_custom_config(json!({ "condition": "status == `200`" })));
+        wf.add_node(Node::new("left", "counting"));
+        wf.add_node(
+            Node::new("right", "counting").with_input_mapping(InputMapping::Single("`[{\"id\": 1, \"r\": true}]`".into())),
+        );
+        wf.add_node(Node::new("merge", MERGE_NODE_TYPE).with_custom_config(json!({ "mode": mode, "key": "id" })));
+        wf.connect_nodes("start", "check").unwrap();
+        wf.connect_nodes_on_output("check", IF_TRUE_OUTPUT, "left").unwrap();
+        wf.connect_nodes("left", "merge").unwrap();
+        wf.connect_nodes("right", "merge").unwrap();
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_merge_keeps_input_positions_when_a_parent_is_skipped() {
+        let mut registry = if_registry(Arc::new(AtomicUsize::new(0)));
+        registry.register(Arc::new(MergeHandler::new()));
+
+        let results = skipped_merge_workflow(200, "left_join").run(&registry).await.unwrap();
+        assert_eq!(results["merge"], json!([{ "id": 1, "status": 200, "r": true }]));
+
+        // 被跳过的 left 仍然占据第一个输入，right 不会被当作左侧输入
+        let results = skipped_merge_workflow(500, "left_join").run(&registry).await.unwrap();
+        assert!(!results.contains_key("left"));
+        assert_eq!(results["merge"], json!([]));
+        let results = skipped_merge_workflow(500, "append").run(&registry).await.unwrap();
+        assert_eq!(results["merge"], json!([{ "id": 1, "r": true }]));
+    }
+
+    fn item_registry(counter: Arc<AtomicUsize>) -> NodeRegistry {
+        let mut registry = if_registry(counter);
+        registry.register(Arc::new(ItemsNode));
+        registry.register(Arc::new(BinaryNode));
+        registry
+    }
+
+    fn source_node(items: &str) -> Node {
+        Node::new("source", "counting").with_input_mapping(InputMapping::Single(format!("`{items}`")))
+    }
+
+    #[tokio::test]
+    async fn test_run_once_for_each_item() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        wf.add_node(source_node("[1, 2, 3]"));
+        wf.add_node(Node::new("per", "counting").with_execution_mode(ItemExecutionMode::RunOnceForEachItem));
+        wf.add_node(Node::new("inspect", "items"));
+        wf.connect_nodes("source", "per").unwrap();
+        wf.connect_nodes("per", "inspect").unwrap();
+
+        let result = wf.execute(&item_registry(counter.clone())).await;
+        assert!(result.is_success());
+        assert_eq!(counter.load(Ordering::SeqCst), 4, "source once + one run per item");
+        assert_eq!(result.outputs["per"], json!([1, 2, 3]));
+        assert_eq!(
+            result.outputs["inspect"],
+            json!([
+                { "json": 1, "paired_item": { "item": 0, "input": 0 } },
+                { "json": 2, "paired_item": { "item": 1, "input": 0 } },
+                { "json": 3, "paired_item": { "item": 2, "input": 0 } }
+            ])
+        );
+        let items: Vec<_> = result.node_runs["per"].attempts.iter().map(|a| a.item).collect();
+        assert_eq!(items, vec![Some(0), Some(1), Some(2)]);
+    }
+
+    /// 测试用节点：以（解析表达式后的）参数作为输出
+    struct ParamsNode;
+
+    #[async_trait]
+    impl NodeType for ParamsNode {
+        fn name(&self) -> &str {
+            "params"
+        }
+        fn display_name(&self) -> &str {
+            "Params Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            Ok(NodeOutput::new(ctx.parameters.clone()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parameter_expressions_are_resolved() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut registry = counting_registry(counter);
+        registry.register(Arc::new(ParamsNode));
+        let mut wf = Workflow::new(None);
+        wf.add_node(source_node(r#"{"count": 3, "name": "batch"}"#));
+        wf.add_node(Node::new("check", "params").with_custom_config(json!({
+            "count": "{{ $json[\"count\"] }}",
+            "label": "{{ $node[\"source\"].json[\"name\"] }} #{{ $json[\"count\"] }}",
+            "unknown": "{{ $json[\"missing\"] }}",
+        })));
+        wf.add_node(
+            Node::new("rows", "counting").with_input_mapping(InputMapping::Single(r#"`[{"n": 1}, {"n": 2}]`"#.into())),
+        );
+        wf.add_node(
+            Node::new("per", "params")
+                .with_custom_config(json!({ "n": "{{ $json[\"n\"] }}", "name": "{{ $node[\"source\"].json[\"name\"] }}" }))
+                .with_execution_mode(ItemExecutionMode::RunOnceForEachItem),
+        );
+        wf.connect_nodes("source", "check").unwrap();
+        wf.connect_nodes("check", "rows").unwrap();
+        wf.connect_nodes("rows", "per").unwrap();
+
+        let result = wf.execute(&registry).await;
+        assert!(result.is_success(), "{:?}", result.error);
+        assert_eq!(
+            result.outputs["check"],
+            json!({ "count": 3, "label": "batch #3", "unknown": null })
+        );
+        assert_eq!(result.outputs["per"], json!([{ "n": 1, "name": "batch" }, { "n": 2, "name": "batch" }]));
+    }
+
+    #[tokio::test]
+    async fn test_jq_input_mapping() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        wf.add_node(source_node(r#"{"items": [{"sku": "a", "price": 5}, {"sku": "b", "price": 12}, {"sku": "c", "price": 40}]}"#));
+        let mapping: InputMapping =
+            serde_json::from_value(json!({ "jq": ".items[] | select(.price > 10) | {sku, from: $node.source.json.items[0].sku}" }))
+                .unwrap();
+        wf.add_node(Node::new("expensive", "counting").with_input_mapping(mapping));
+        wf.connect_nodes("source", "expensive").unwrap();
+
+        let result = wf.execute(&counting_registry(counter)).await;
+        assert!(result.is_success(), "{:?}", result.error);
+        assert_eq!(
+            result.outputs["expensive"],
+            json!([{ "sku": "b", "from": "a" }, { "sku": "c", "from": "a" }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_parameter_expression_fails_node() {
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(ParamsNode));
+        let mut wf = Workflow::new(None);
+        wf.add_node(Node::new("check", "params").with_custom_config(json!({ "n": "value: {{ count + }}" })));
+
+        let result = wf.execute(&registry).await;
+        let error = result.error.as_ref().unwrap();
+        assert_eq!(error.node.as_deref(), Some("check"));
+        assert!(error.message.contains("line 1, column"), "{}", error.message);
+        assert!(!result.outputs.contains_key("check"));
+    }
+
+    #[tokio::test]
+    async fn test_each_item_routing_through_if_node() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        wf.add_node(source_node(r#"[{"v": 1}, {"v": 20}, {"v": 30}]"#));
+        wf.add_node(
+            Node::new("check", IF_NODE_TYPE)
+                .with_custom_config(json!({ "condition": "v > `10`" }))
+                .with_execution_mode(ItemExecutionMode::RunOnceForEachItem),
+        );
+        wf.add_node(Node::new("big", "counting"));
+        wf.add_node(Node::new("small", "counting"));
+        wf.connect_nodes("source", "check").unwrap();
+        wf.connect_nodes_on_output("check", IF_TRUE_OUTPUT, "big").unwrap();
+        wf.connect_nodes_on_output("check", IF_FALSE_OUTPUT, "small").unwrap();
+
+        let results = wf.run(&item_registry(counter)).await.unwrap();
+        assert_eq!(results["big"], json!([{ "v": 20 }, { "v": 30 }]));
+        assert_eq!(results["small"], json!([{ "v": 1 }]));
+    }
+
+    #[tokio::test]
+    async fn test_binary_data_reaches_child_items() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(None);
+        wf.add_node(Node::new("download", "binary"));
+        wf.add_node(Node::new("inspect", "items"));
+        wf.connect_nodes("download", "inspect").unwrap();
+
+        let results = wf.run(&item_registry(counter)).await.unwrap();
+        assert_eq!(results["download"], json!([{ "file": "hello.txt" }]));
+        assert_eq!(results["inspect"][0]["binary"]["file"]["mime_type"], json!("text/plain"));
+        assert_eq!(results["inspect"][0]["paired_item"], json!({ "item": 0, "input": 0 }));
+    }
+
+    /// expensive(counting) -> next(delay)
+    fn pinned_workflow() -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(Node::new("expensive", "counting"));
+        wf.add_node(delay_node("next", 0));
+        wf.connect_nodes("expensive", "next").unwrap();
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_pinned_node_is_not_executed() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let registry = counting_registry(counter.clone());
+        let mut wf = pinned_workflow();
+        wf.pin_node_data("expensive", json!({ "cached": true })).unwrap();
+
+        let options = ExecutionOptions::default().with_use_pin_data(true);
+        let result = wf.execute_with_options(&registry, &options).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        assert!(result.node_runs["expensive"].pinned);
+        assert_eq!(result.outputs["next"]["input"], json!({ "cached": true }));
+
+        // 未开启 use_pin_data 时照常执行
+        let result = wf.execute(&registry).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(!result.node_runs["expensive"].pinned);
+        assert_eq!(result.outputs["expensive"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_pin_last_output() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let registry = counting_registry(counter.clone());
+        let mut wf = pinned_workflow();
+        wf.settings = json!({ "use_pin_data": true });
+
+        let first = wf.execute(&registry).await;
+        wf.pin_last_output("expensive", &first).unwrap();
+        assert_eq!(wf.pin_data["expensive"], json!({}));
+
+        let second = wf.execute(&registry).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(wf.pin_last_output("expensive", &second).is_err());
+        assert!(wf.pin_node_data("missing", json!(1)).is_err());
+
+        assert_eq!(wf.unpin_node_data("expensive"), Some(json!({})));
+        wf.execute(&registry).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    /// source(counting) -> a -> b，外加与 a 无关的分支 side
+    fn partial_workflow() -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(Node::new("source", "counting"));
+        wf.add_node(delay_node("a", 0));
+        wf.add_node(delay_node("b", 0));
+        wf.add_node(delay_node("side", 0));
+        wf.connect_nodes("source", "a").unwrap();
+        wf.connect_nodes("a", "b").unwrap();
+        wf.connect_nodes("source", "side").unwrap();
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_run_until_executes_only_ancestors() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let registry = counting_registry(counter.clone());
+        let wf = partial_workflow();
+
+        let results = wf.run_until(&registry, "a").await.unwrap();
+        let mut executed: Vec<_> = results.keys().cloned().collect();
+        executed.sort();
+        assert_eq!(executed, vec!["a", "source"]);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        assert!(wf.run_until(&registry, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_from_reuses_prior_parent_outputs() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let registry = counting_registry(counter.clone());
+        let wf = partial_workflow();
+
+        let prior = HashMap::from([("source".to_string(), json!({ "cached": true }))]);
+        let result = wf.execute_from(&registry, "a", &prior).await;
+        assert!(result.is_success());
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        assert!(result.node_runs["source"].reused);
+        assert!(result.node_runs["source"].attempts.is_empty());
+        assert!(!result.outputs.contains_key("side"));
+        assert_eq!(result.outputs["source"], json!({ "cached": true }));
+        assert_eq!(result.outputs["a"]["input"], json!({ "cached": true }));
+        assert_eq!(result.outputs["b"]["input"]["node"], json!("a"));
+
+        // 起始节点的父节点没有历史输出
+        let err = wf.run_from(&registry, "a", &HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("source"));
+
+        // 两个连接索引不一致时返回错误而不是 panic
+        let mut inconsistent = partial_workflow();
+        inconsistent.connections_by_source.remove("source");
+        let err = inconsistent.run_from(&registry, "a", &prior).await.unwrap_err();
+        assert!(err.to_string().contains("source"));
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        // 没有父节点的起始节点不需要历史输出
+        let results = wf.run_from(&registry, "source", &HashMap::new()).await.unwrap();
+        assert_eq!(results.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_node_timeout_is_reported_and_retried() {
+        let mut wf = Workflow::new(None);
+        wf.add_node(
+            delay_node("slow", 500)
+                .with_timeout_ms(50)
+                .with_retry_policy(
+                    RetryPolicy::new(2)
+                        .with_backoff(Backoff::Fixed { delay_ms: 0 })
+                        .with_retry_on(vec![NodeErrorKind::Timeout]),
+                ),
+        );
+
+        let start = Instant::now();
+        let result = wf.execute(&registry()).await;
+        assert!(start.elapsed() < Duration::from_millis(400));
+        assert_eq!(result.error.as_ref().unwrap().kind, NodeErrorKind::Timeout);
+        assert_eq!(result.node_runs["slow"].attempts.len(), 2);
+        assert!(matches!(result.into_outputs(), Err(NodeError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_timeout_aborts_run() {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("fast", 0));
+        wf.add_node(delay_node("slow", 5_000));
+        wf.connect_nodes("fast", "slow").unwrap();
+        wf.settings = json!({ "timeout_ms": 100 });
+
+        let start = Instant::now();
+        let result = wf.execute(&registry()).await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+        let err = result.error.as_ref().unwrap();
+        assert_eq!((err.kind, err.node.as_deref()), (NodeErrorKind::Timeout, None));
+        assert!(result.outputs.contains_key("fast"));
+        assert!(!result.outputs.contains_key("slow"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_workflow() {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("hang", 60_000));
+
+        let handle = wf.start(Arc::new(registry()), ExecutionOptions::default());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!handle.is_finished());
+
+        let start = Instant::now();
+        handle.cancel();
+        let result = handle.wait().await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(result.error.unwrap().kind, NodeErrorKind::Cancelled);
+        assert!(result.outputs.is_empty());
+
+        // 启动前已经取消的令牌
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = ExecutionOptions::default().with_cancel_token(token);
+        let err = wf.run_with_options(&registry(), &options).await.unwrap_err();
+        assert!(matches!(err, NodeError::Cancelled(_)));
+    }
+
+    #[tokio::test]
+    async fn test_execution_events_are_streamed_in_order() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut wf = Workflow::new(Some("events".into()));
+        wf.add_node(flaky_node(
+            json!({ "fail_times": 1 }),
+            RetryPolicy::new(2).with_backoff(Backoff::Fixed { delay_ms: 0 }),
+        ));
+        wf.add_node(delay_node("next", 0).with_custom_config(json!({ "fail": true })));
+        wf.connect_nodes("flaky", "next").unwrap();
+
+        let (tx, mut rx) = event_channel();
+        let options = ExecutionOptions::default().with_events(tx);
+        let result = wf.execute_with_options(&flaky_registry(counter), &options).await;
+        drop(options);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        let next_error = result.error.clone().unwrap();
+        assert_eq!(
+            events,
+            vec![
+                ExecutionEvent::RunStarted { workflow_id: Some("events".into()) },
+                ExecutionEvent::NodeStarted { node: "flaky".into() },
+                ExecutionEvent::NodeRetrying {
+                    node: "flaky".into(),
+                    next_attempt: 2,
+                    delay_ms: 0,
+                    error: "Execution failed: transient failure #1".into(),
+                },
+                ExecutionEvent::NodeFinished {
+                    node: "flaky".into(),
+                    summary: OutputSummary { item_count: 1, outputs: vec![MAIN_OUTPUT.into()] },
+                    pinned: false,
+                },
+                ExecutionEvent::NodeStarted { node: "next".into() },
+                ExecutionEvent::NodeFailed { node: "next".into(), error: next_error.clone() },
+                ExecutionEvent::RunFinished { error: Some(next_error) },
+            ]
+        );
+
+        let value = serde_json::to_value(&events[1]).unwrap();
+        assert_eq!(value, json!({ "type": "node_started", "node": "flaky" }));
+    }
+
+    fn loop_registry(counter: Arc<AtomicUsize>) -> NodeRegistry {
+        let mut registry = counting_registry(counter);
+        registry.register(Arc::new(SplitInBatchesHandler::new()));
+        registry
+    }
+
+    /// source -> loop -(loop)-> body -> loop -(done)-> after，source 的输出由 Pin 数据给出
+    fn loop_workflow(source: Value, batch_size: usize) -> Workflow {
+        let mut wf = Workflow::new(None);
+        wf.add_node(delay_node("source", 0));
+        wf.add_node(
+            Node::new("loop", SPLIT_IN_BATCHES_NODE_TYPE).with_custom_config(json!({ "batch_size": batch_size })),
+        );
+        wf.add_node(Node::new("body", "counting"));
+        wf.add_node(delay_node("after", 0));
+        wf.connect_nodes("source", "loop").unwrap();
+        wf.connect_nodes_on_output("loop", LOOP_OUTPUT, "body").unwrap();
+        wf.connect_nodes("body", "loop").unwrap();
+        wf.connect_nodes_on_output("loop", DONE_OUTPUT, "after").unwrap();
+        wf.pin_node_data("source", source).unwrap();
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_loop_runs_body_once_per_batch() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let registry = loop_registry(counter.clone());
+        let numbers = json!([{ "n": 1 }, { "n": 2 }, { "n": 3 }, { "n": 4 }, { "n": 5 }]);
+        let wf = loop_workflow(numbers.clone(), 2);
+        let options = ExecutionOptions::default().with_use_pin_data(true);
+
+        let result = wf.execute_with_options(&registry, &options).await;
+        assert!(result.error.is_none(), "{:?}", result.error);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        assert_eq!(result.outputs["body"], json!([{ "n": 5 }]));
+        assert_eq!(result.outputs["loop"], numbers);
+        assert_eq!(result.outputs["after"]["input"], numbers);
+        assert_eq!(result.node_runs["loop"].outputs, vec![DONE_OUTPUT]);
+    }
+
+    #[tokio::test]
+    async fn test_loop_with_empty_input_skips_body() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let registry = loop_registry(counter.clone());
+        let wf = loop_workflow(json!([]), 2);
+        let options = ExecutionOptions::default().with_use_pin_data(true);
+
+        let result = wf.execute_with_options(&registry, &options).await;
+        assert!(result.error.is_none(), "{:?}", result.error);
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        assert!(!result.outputs.contains_key("body"));
+        assert_eq!(result.outputs["after"]["input"], json!([]));
+    }
+
+    /// 测试用节点：返回执行上下文中的 globals 与 env，以及映射后的输入
+    struct ContextNode;
+
+    #[async_trait]
+    impl NodeType for ContextNode {
+        fn name(&self) -> &str {
+            "context"
+        }
+        fn display_name(&self) -> &str {
+            "Context Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            Ok(NodeOutput::new(json!({ "globals": ctx.globals, "env": ctx.env, "input": ctx.input_data })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_context_reaches_nodes_and_mappings() {
+        std::env::set_var("AF_EXECUTOR_TEST_REGION", "eu-west");
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(ContextNode));
+        let mut wf = Workflow::new(Some("wf-ctx".into()));
+        wf.settings = json!({ "timezone": "Europe/Berlin" });
+        let fields = [
+            ("workflow", "$globals.workflow_id"),
+            ("timezone", "$globals.timezone"),
+            ("region", "$env.AF_EXECUTOR_TEST_REGION"),
+        ];
+        wf.add_node(Node::new("ctx", "context").with_input_mapping(InputMapping::Multi {
+            fields: fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            defaultValue: None,
+        }));
+        let options = ExecutionOptions::default().with_env_vars(["AF_EXECUTOR_TEST_REGION"]);
+
+        let result = wf.execute_with_options(&registry, &options).await;
+        assert!(result.is_success(), "{:?}", result.error);
+        let output = &result.outputs["ctx"];
+        assert_eq!(output["globals"]["workflow_id"], json!("wf-ctx"));
+        assert_eq!(output["globals"]["execution_id"], Value::Null);
+        assert!(output["globals"]["started_at"].is_string());
+        assert_eq!(output["env"], json!({ "AF_EXECUTOR_TEST_REGION": "eu-west" }));
+        assert_eq!(
+            output["input"],
+            json!({ "workflow": "wf-ctx", "timezone": "Europe/Berlin", "region": "eu-west" })
+        );
+
+        let result = wf.execute(&registry).await;
+        assert_eq!(result.outputs["ctx"]["env"], json!({}), "env vars outside the whitelist stay hidden");
+    }
+
+    #[test]
+    fn test_item_execution_modes_match_constants() {
+        let modes = [ItemExecutionMode::RunOnceForAllItems, ItemExecutionMode::RunOnceForEachItem];
+        for (mode, name) in modes.iter().zip(crate::constants::CODE_EXECUTION_MODES) {
+            assert_eq!(serde_json::to_value(mode).unwrap(), json!(name));
+        }
+    }
+
+    #[test]
+    fn test_options_from_settings() {
+        let options = ExecutionOptions::from_settings(
+            &json!({ "max_parallelism": 3, "use_pin_data": true, "timeout_ms": 1500 }),
+        );
+        assert_eq!(options.max_parallelism, 3);
+        assert!(options.use_pin_data);
+        assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+        let options = ExecutionOptions::from_settings(&json!({}));
+        assert_eq!(options.max_parallelism, DEFAULT_MAX_PARALLELISM);
+    }
+}
@@ -0,0 +1,360 @@
+// src/expression.rs
+
+//! 表达式求值的统一入口：节点参数、input_mapping、数据转换以及 If / Switch 的条件（见 [`NodeExpressions`]）都经由这里求值。
+//!
+//! - 表达式主体是完整的 JMESPath（使用 [`CUSTOM_RUNTIME`]），`@` 以及不带前缀的字段都指向当前输入数据；
+//! - 可用的变量：`$json`（当前输入数据）、`$node`（节点 ID -> `{ "json": 输出 }`）、
+//!   `$globals` / `$env`（见 [`RunContext`]）以及 `$now`（求值时刻，RFC 3339）；
+//! - 模板字符串中的 `{{ ... }}` 会被求值：整个字符串只是一个表达式时保留结果的类型，
+//!   否则结果以文本形式插入（`null` 插入空串）；n8n 的 `=` 前缀写法同样支持；
+//! - 解析和求值错误都带有在原始字符串中的位置。
+//!
+//! 旧写法 `$json["city"]`、`$node["X"].json["field"]` 仍然有效。
+
+use std::collections::{BTreeMap, HashMap};
+use alphaflow_jmes::{Arcvar, JmespathError, ToJmespath};
+use alphaflow_nodes::node_type::{ExpressionEvaluator, NodeError};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use crate::jmes_runtime::CUSTOM_RUNTIME;
+use crate::run_context::RunContext;
+
+/// 节点执行结果: node_id -> JSON
+pub type NodeResults = HashMap<String, Value>;
+
+/// 表达式中可以使用的变量名（不含 `$`）
+pub(crate) const VARIABLES: [&str; 5] = ["json", "node", "globals", "env", "now"];
+
+/// 表达式错误，位置相对于完整的表达式或模板字符串
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{reason} (line {line}, column {column} in `{expression}`)")]
+pub struct ExpressionError {
+    /// 出错的表达式或模板
+    pub expression: String,
+    /// 出错位置的字符偏移（从 0 开始）
+    pub offset: usize,
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 列号（从 1 开始）
+    pub column: usize,
+    /// 错误原因
+    pub reason: String,
+}
+
+impl ExpressionError {
+    pub(crate) fn at(expression: &str, offset: usize, reason: impl Into<String>) -> Self {
+        let (mut line, mut column) = (1, 1);
+        for c in expression.chars().take(offset) {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        Self {
+            expression: expression.to_string(),
+            offset,
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// 表达式可以访问的数据
+#[derive(Debug, Clone)]
+pub struct ExpressionScope<'a> {
+    json: &'a Value,
+    nodes: Option<&'a NodeResults>,
+    run: Option<&'a RunContext>,
+    now: DateTime<Utc>,
+}
+
+impl<'a> ExpressionScope<'a> {
+    /// 以 `json` 作为当前输入数据，`$now` 取当前时间
+    pub fn new(json: &'a Value) -> Self {
+        Self { json, nodes: None, run: None, now: Utc::now() }
+    }
+
+    /// 通过 `$node` 访问已执行节点的输出
+    pub fn with_nodes(mut self, nodes: &'a NodeResults) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+
+    /// 通过 `$globals` / `$env` 访问运行上下文
+    pub fn with_run(mut self, run: &'a RunContext) -> Self {
+        self.run = Some(run);
+        self
+    }
+
+    /// 固定 `$now` 的取值
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// 相同的上下文，但换成另一份输入数据（例如逐项执行时的单个数据项）
+    pub fn with_json(&self, json: &'a Value) -> Self {
+        Self { json, ..self.clone() }
+    }
+
+    /// 当前输入数据
+    pub(crate) fn json(&self) -> &'a Value {
+        self.json
+    }
+
+    /// 表达式是否引用了变量 `name`（按名称粗略匹配，只转换用得到的数据）
+    pub(crate) fn references(expression: &str, name: &str) -> bool {
+        expression.contains(&format!("${}", name))
+    }
+
+    /// 变量 `name`（[`VARIABLES`] 之一）的取值
+    pub(crate) fn variable(&self, name: &str) -> Value {
+        match name {
+            "json" => self.json.clone(),
+            "node" => Value::Object(
+                self.nodes
+                    .into_iter()
+                    .flatten()
+                    .map(|(id, output)| (id.clone(), json!({ "json": output })))
+                    .collect::<Map<_, _>>(),
+            ),
+            "globals" => self.run.map_or_else(|| json!({}), |run| run.globals.clone()),
+            "env" => self.run.map_or_else(|| json!({}), |run| run.env.clone()),
+            _ => Value::String(self.now.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        }
+    }
+
+    /// JMESPath 求值时绑定的变量
+    fn variables(&self, expression: &str) -> Result<BTreeMap<String, Arcvar>, JmespathError> {
+        let mut variables = BTreeMap::new();
+        for name in VARIABLES.into_iter().filter(|name| Self::references(expression, name)) {
+            variables.insert(name.to_string(), self.variable(name).to_jmespath()?);
+        }
+        Ok(variables)
+    }
+}
+
+/// 对一个 JMESPath 表达式求值（不是模板，不需要 `{{ }}`）
+pub fn evaluate(expression: &str, scope: &ExpressionScope<'_>) -> Result<Value, ExpressionError> {
+    evaluate_body(expression, expression, 0, scope)
+}
+
+/// 递归解析 JSON 中所有字符串里的模板
+pub fn render(value: &Value, scope: &ExpressionScope<'_>) -> Result<Value, ExpressionError> {
+    match value {
+        Value::String(template) => render_str(template, scope),
+        Value::Array(items) => items.iter().map(|item| render(item, scope)).collect::<Result<_, _>>().map(Value::Array),
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, item)| Ok((key.clone(), render(item, scope)?)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        _ => Ok(value.clone()),
+    }
+}
+
+/// 解析一个模板字符串，不包含 `{{ }}` 的字符串原样返回
+pub fn render_str(template: &str, scope: &ExpressionScope<'_>) -> Result<Value, ExpressionError> {
+    let start = usize::from(template.starts_with('=') && template.contains("{{"));
+    let segments = parse_template(template, start)?;
+    if !segments.iter().any(|segment| matches!(segment, Segment::Expression { .. })) {
+        return Ok(Value::String(template.to_string()));
+    }
+
+    // 去掉首尾空白后只剩一个表达式：保留求值结果的类型
+    let is_blank = |segment: &Segment<'_>| matches!(segment, Segment::Text(text) if text.trim().is_empty());
+    let mut expressions = segments.iter().filter(|segment| !is_blank(segment));
+    if let (Some(Segment::Expression { body, offset }), None) = (expressions.next(), expressions.next()) {
+        return evaluate_body(template, body, *offset, scope);
+    }
+
+    let mut output = String::new();
+    for segment in &segments {
+        match segment {
+            Segment::Text(text) => output.push_str(text),
+            Segment::Expression { body, offset } => {
+                output.push_str(&to_text(&evaluate_body(template, body, *offset, scope)?));
+            }
+        }
+    }
+    Ok(Value::String(output))
+}
+
+/// 交给节点的表达式求值器（[`NodeExecutionContext::expressions`]），`$json` 由节点在求值时传入
+///
+/// [`NodeExecutionContext::expressions`]: alphaflow_nodes::node_type::NodeExecutionContext::expressions
+#[derive(Debug)]
+pub(crate) struct NodeExpressions {
+    nodes: NodeResults,
+    run: RunContext,
+}
+
+impl NodeExpressions {
+    /// 为参数为 `parameters` 的节点构造求值器；参数中没有引用 `$node` 时不复制已执行节点的输出
+    pub(crate) fn new(parameters: &Value, nodes: &NodeResults, run: &RunContext) -> Self {
+        let nodes = if ExpressionScope::references(&parameters.to_string(), "node") {
+            nodes.clone()
+        } else {
+            NodeResults::new()
+        };
+        Self { nodes, run: run.clone() }
+    }
+}
+
+impl ExpressionEvaluator for NodeExpressions {
+    fn evaluate(&self, expression: &str, json: &Value) -> Result<Value, NodeError> {
+        let scope = ExpressionScope::new(json).with_nodes(&self.nodes).with_run(&self.run);
+        evaluate(expression, &scope).map_err(|e| NodeError::InvalidConfig(e.to_string()))
+    }
+}
+
+/// 插值时的文本形式
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// 求值 `source` 中从字符偏移 `offset` 开始的表达式主体 `body`
+fn evaluate_body(source: &str, body: &str, offset: usize, scope: &ExpressionScope<'_>) -> Result<Value, ExpressionError> {
+    let positioned = |e: JmespathError| ExpressionError::at(source, offset + e.offset, e.reason.to_string());
+    let compiled = CUSTOM_RUNTIME.compile(body).map_err(positioned)?;
+    let variables = scope.variables(body).map_err(positioned)?;
+    let result = compiled.search_with_variables(scope.json, variables).map_err(positioned)?;
+    serde_json::to_value(&*result).map_err(|e| ExpressionError::at(source, offset, e.to_string()))
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment<'t> {
+    Text(&'t str),
+    /// `offset` 为表达式主体在模板中的字符偏移
+    Expression { body: &'t str, offset: usize },
+}
+
+/// 从字节位置 `start` 开始把模板拆分为文本和 `{{ ... }}` 表达式
+fn parse_template(template: &str, start: usize) -> Result<Vec<Segment<'_>>, ExpressionError> {
+    let char_offset = |byte: usize| template[..byte].chars().count();
+    let mut segments = Vec::new();
+    let mut rest = start;
+    while let Some(found) = template[rest..].find("{{") {
+        let open = rest + found;
+        if open > rest {
+            segments.push(Segment::Text(&template[rest..open]));
+        }
+        let body_start = open + 2;
+        let body_end = closing_braces(&template[body_start..])
+            .map(|end| body_start + end)
+            .ok_or_else(|| ExpressionError::at(template, char_offset(open), "Unclosed '{{'"))?;
+        segments.push(Segment::Expression {
+            body: &template[body_start..body_end],
+            offset: char_offset(body_start),
+        });
+        rest = body_end + 2;
+    }
+    if rest < template.len() {
+        segments.push(Segment::Text(&template[rest..]));
+    }
+    Ok(segments)
+}
+
+/// 查找与 `{{` 配对的 `}}`，跳过字符串、字面量以及 multi-select hash 中的花括号
+fn closing_braces(body: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            '}' if body[i + 1..].starts_with('}') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn results() -> NodeResults {
+        let mut results = NodeResults::new();
+        results.insert("PrevNode".to_string(), json!({ "message": "Hello", "arr": [{ "val": 10 }, { "val": 20 }] }));
+        results
+    }
+
+    #[test]
+    fn test_legacy_syntax_still_resolves() {
+        let input = json!({ "city": "Berlin", "arr": ["A", "B", "C"], "location": { "capital": { "name": "London" } } });
+        let results = results();
+        let scope = ExpressionScope::new(&input).with_nodes(&results);
+        let params = json!({
+            "title": "Hello, {{ $json[\"city\"] }} is cool",
+            "index1": "{{ $json[\"arr\"][1] }}",
+            "nested": { "name": "{{ $json[\"location\"][\"capital\"][\"name\"] }}" },
+            "node": "{{ $node[\"PrevNode\"].json[\"arr\"][1][\"val\"] }}",
+            "n8n": "={{ $node[\"PrevNode\"].json[\"message\"] }}"
+        });
+
+        let resolved = render(&params, &scope).unwrap();
+        assert_eq!(resolved["title"], json!("Hello, Berlin is cool"));
+        assert_eq!(resolved["index1"], json!("B"));
+        assert_eq!(resolved["nested"]["name"], json!("London"));
+        assert_eq!(resolved["node"], json!(20));
+        assert_eq!(resolved["n8n"], json!("Hello"));
+    }
+
+    #[test]
+    fn test_full_jmespath_and_variables() {
+        let input = json!({ "count": 3, "items": [{ "name": "a", "price": 5 }, { "name": "b", "price": 7 }] });
+        let results = results();
+        let run = RunContext { globals: json!({ "workflow_id": "wf-1" }), ..Default::default() };
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap();
+        let scope = ExpressionScope::new(&input).with_nodes(&results).with_run(&run).with_now(now);
+
+        assert_eq!(render_str("{{ items[*].name }}", &scope).unwrap(), json!(["a", "b"]));
+        assert_eq!(render_str(" {{ sum(items[*].price) }} ", &scope).unwrap(), json!(12.0));
+        assert_eq!(render_str("{{ {n: count, first: $json.items[0].name} }}", &scope).unwrap(), json!({ "n": 3, "first": "a" }));
+        assert_eq!(
+            render_str("{{ $globals.workflow_id }}@{{ $now }}: {{ $env.MISSING }}{{ length($node.PrevNode.json.arr) }}", &scope).unwrap(),
+            json!("wf-1@2025-03-01T08:00:00.000Z: 2")
+        );
+        assert_eq!(render_str("no templates = {}", &scope).unwrap(), json!("no templates = {}"));
+        assert_eq!(evaluate("count", &scope).unwrap(), json!(3));
+    }
+
+    #[test]
+    fn test_errors_report_positions() {
+        let input = json!({});
+        let scope = ExpressionScope::new(&input);
+
+        let err = render_str("ok {{ foo[ }}", &scope).unwrap_err();
+        assert_eq!(err.expression, "ok {{ foo[ }}");
+        assert_eq!((err.line, err.column), (1, 12));
+        assert!(err.reason.starts_with("Parse error"), "{}", err);
+
+        let err = render_str("line one\n{{ $missing }}", &scope).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert!(err.reason.contains("undefined variable $missing"), "{}", err);
+
+        let err = render_str("a {{ b", &scope).unwrap_err();
+        assert_eq!((err.offset, err.reason.as_str()), (2, "Unclosed '{{'"));
+    }
+}
@@ -21,7 +21,7 @@ pub static GLOBAL_STATE: Lazy<RwLock<GlobalState>> = Lazy::new(|| {
 ///
 /// # 示例
 /// ```rust
-/// use crate::global_state::{set_global_state, GlobalState};
+/// use alphaflow_workflow::global_state::{set_global_state, GlobalState};
 ///
 /// let new_state = GlobalState { default_timezone: "Europe/London".to_string() };
 /// set_global_state(new_state);
@@ -36,7 +36,7 @@ pub fn set_global_state(state: GlobalState) {
 ///
 /// # 示例
 /// ```rust
-/// use crate::global_state::get_global_state;
+/// use alphaflow_workflow::global_state::get_global_state;
 ///
 /// let state = get_global_state();
 /// println!("当前默认时区: {}", state.default_timezone);
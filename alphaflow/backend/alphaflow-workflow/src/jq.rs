@@ -0,0 +1,138 @@
+// src/jq.rs
+
+//! jq 过滤器（基于 jaq），作为 JMESPath 之外的另一种映射语言，例如 `.items[] | select(.price > 10)`。
+//!
+//! - 过滤器可以使用与 JMESPath 表达式相同的变量：`$json`、`$node`、`$globals`、`$env`、`$now`
+//!   （见 [`crate::expression`]）；
+//! - 过滤器没有输出时结果为 `null`，只有一个输出时为该值，有多个输出时收集为数组；
+//! - jaq 1.x 的核心库不包含 jq 标准库中以 jq 定义的过滤器，常用的部分见 `jq_prelude.jq`；
+//!   读取全部环境变量的 `env` 不可用，请使用白名单中的 `$env`；
+//! - 与 jq 不同，jaq 对 `null` 取字段会报错，可以写成 `.a.b?`。
+//!
+//! 编译后的过滤器按源码缓存，同一个映射在多次运行中只编译一次。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use jaq_interpret::{Ctx, Filter, FilterT, ParseCtx, RcIter, Val};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use crate::expression::{ExpressionError, ExpressionScope, VARIABLES};
+
+/// 缓存的过滤器数量上限，超出时清空缓存
+const FILTER_CACHE_CAPACITY: usize = 256;
+
+/// 不对过滤器开放的原生过滤器
+const EXCLUDED_NATIVES: [&str; 2] = ["env", "inputs"];
+
+static PRELUDE: Lazy<Vec<jaq_syn::Def>> = Lazy::new(|| {
+    let (defs, errs) = jaq_parse::parse(include_str!("jq_prelude.jq"), jaq_parse::defs());
+    assert!(errs.is_empty(), "invalid jq prelude: {:?}", errs);
+    defs.unwrap_or_default()
+});
+
+static FILTER_CACHE: Lazy<Mutex<HashMap<String, Arc<Filter>>>> = Lazy::new(Default::default);
+
+/// 以 `scope` 中的数据执行 jq 过滤器
+pub fn evaluate(filter: &str, scope: &ExpressionScope<'_>) -> Result<Value, ExpressionError> {
+    let compiled = compile(filter)?;
+    let vars = VARIABLES.map(|name| {
+        if ExpressionScope::references(filter, name) {
+            Val::from(scope.variable(name))
+        } else {
+            Val::Null
+        }
+    });
+    let inputs = RcIter::new(core::iter::empty());
+    let mut outputs = compiled
+        .run((Ctx::new(vars, &inputs), Val::from(scope.json().clone())))
+        .map(|output| output.map(Value::from))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ExpressionError::at(filter, 0, e.to_string()))?;
+    Ok(match outputs.len() {
+        0 => Value::Null,
+        1 => outputs.remove(0),
+        _ => Value::Array(outputs),
+    })
+}
+
+/// 编译过滤器，优先使用缓存
+fn compile(filter: &str) -> Result<Arc<Filter>, ExpressionError> {
+    if let Some(cached) = lock_cache().get(filter) {
+        return Ok(cached.clone());
+    }
+
+    let (main, errs) = jaq_parse::parse(filter, jaq_parse::main());
+    if let Some(err) = errs.first() {
+        return Err(ExpressionError::at(filter, err.span().start, format!("Parse error: {}", err)));
+    }
+    let main = main.ok_or_else(|| ExpressionError::at(filter, 0, "Parse error: empty filter"))?;
+
+    let mut ctx = ParseCtx::new(VARIABLES.iter().map(|name| name.to_string()).collect());
+    ctx.insert_natives(jaq_core::core().filter(|(name, _, _)| !EXCLUDED_NATIVES.contains(&name.as_str())));
+    ctx.insert_defs(PRELUDE.iter().cloned());
+    let compiled = ctx.compile(main);
+    if let Some((err, span)) = ctx.errs.first() {
+        let name: String = filter.chars().skip(span.start).take(span.len()).collect();
+        return Err(ExpressionError::at(filter, span.start, format!("Compile error: {} `{}`", err, name)));
+    }
+
+    let compiled = Arc::new(compiled);
+    let mut cache = lock_cache();
+    if cache.len() >= FILTER_CACHE_CAPACITY {
+        cache.clear();
+    }
+    cache.insert(filter.to_string(), compiled.clone());
+    Ok(compiled)
+}
+
+fn lock_cache() -> std::sync::MutexGuard<'static, HashMap<String, Arc<Filter>>> {
+    // 持有锁期间不会 panic，忽略中毒状态
+    FILTER_CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_context::RunContext;
+    use serde_json::json;
+
+    #[test]
+    fn test_jq_filters() {
+        let input = json!({ "items": [{ "name": "a", "price": 5 }, { "name": "b", "price": 20 }, { "name": "c", "price": 30 }] });
+        let run = RunContext { globals: json!({ "workflow_id": "wf-1" }), ..Default::default() };
+        let scope = ExpressionScope::new(&input).with_run(&run);
+
+        assert_eq!(
+            evaluate(".items[] | select(.price > 10) | .name", &scope).unwrap(),
+            json!(["b", "c"])
+        );
+        assert_eq!(
+            evaluate("[.items[] | select(.price > 25)] | map({name, total: (.price * 2)})", &scope).unwrap(),
+            json!([{ "name": "c", "total": 60 }])
+        );
+        assert_eq!(evaluate(".items | map(.price) | add", &scope).unwrap(), json!(55));
+        assert_eq!(evaluate(".items[] | select(.price > 100)", &scope).unwrap(), Value::Null);
+        assert_eq!(
+            evaluate("{id: $globals.workflow_id, first: $json.items[0].name}", &scope).unwrap(),
+            json!({ "id": "wf-1", "first": "a" })
+        );
+        assert_eq!(evaluate(".items[0] | to_entries | map(.key) | join(\",\")", &scope).unwrap(), json!("name,price"));
+    }
+
+    #[test]
+    fn test_jq_errors_and_cache() {
+        let input = json!({});
+        let scope = ExpressionScope::new(&input);
+
+        let err = evaluate(".a | .[", &scope).unwrap_err();
+        assert!(err.reason.starts_with("Parse error"), "{}", err);
+        let err = evaluate(".a | nope(1)", &scope).unwrap_err();
+        assert_eq!(err.column, 6);
+        assert!(err.reason.contains("undefined filter `nope(1)`"), "{}", err);
+        assert!(evaluate("env", &scope).is_err());
+
+        let filter = ".cached_filter_test";
+        let first = compile(filter).unwrap();
+        assert!(Arc::ptr_eq(&first, &compile(filter).unwrap()));
+    }
+}
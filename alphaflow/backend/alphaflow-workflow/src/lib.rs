@@ -1,9 +1,20 @@
 pub mod constants;
 pub mod transformation;
 pub mod workflow;
-pub mod json_expression;
-pub mod jmse_expression;
+pub mod expression;
 pub mod directed_graph;
 pub mod jmes_runtime;
 pub mod waiting_queue;
-pub mod executor;
\ No newline at end of file
+pub mod executor;
+pub mod validation;
+pub mod run_result;
+pub mod events;
+pub mod persistence;
+pub mod storage;
+pub mod n8n;
+pub mod definition;
+pub mod sub_workflow;
+pub mod wait;
+pub mod global_state;
+pub mod run_context;
+pub mod jq;
@@ -0,0 +1,831 @@
+// src/n8n.rs
+
+//! n8n 工作流导出 JSON 与 [`Workflow`] 之间的转换，用于迁移已有的 n8n 自动化流程。
+//!
+//! 节点类型的对应关系：
+//!
+//! - `n8n-nodes-base.httpRequest` <-> `http`；
+//! - 其余 `n8n-nodes-base.X` <-> `af-nodes-base.X`（例如 [`IF_NODE_TYPE`]、[`MERGE_NODE_TYPE`]、[`SPLIT_IN_BATCHES_NODE_TYPE`]）；
+//! - 其他类型（例如 `@n8n/n8n-nodes-langchain.*`、社区节点）保持原名。
+//!
+//! 导入时，对应类型没有在 [`NodeRegistry`] 中注册的节点仍会保留（连接不变），
+//! 并在 [`N8nImport::unsupported_nodes`] 中列出，这些节点需要替换后工作流才能运行。
+//! 便签节点 (`stickyNote`) 会被忽略。
+//!
+//! 原始的 n8n 参数保存在 [`Node::parameters`] 中，执行时使用的 `custom_config` 只对
+//! 参数格式可以转换的节点（HTTP、Merge、Split In Batches）生成；If / Switch 的条件需要改写为 JMESPath。
+//! 导出时 `typeVersion` 使用 [`type_version`] 中的版本，凭据 (credentials) 不会导入或导出。
+//!
+//! n8n 按下标区分节点的输出，本项目按名称区分，转换规则：
+//!
+//! - If：0 -> `"true"`，1 -> `"false"`；
+//! - Switch：第 i 个输出对应第 i 条规则的 `output`，之后是 `fallback_output`；
+//! - Split In Batches：0 -> `"done"`，1 -> `"loop"`；
+//!   没有规则配置（例如刚导入）时使用下标字符串 `"0"`、`"1"`...；
+//! - 其他节点：0 -> `"main"`；
+//! - `on_error` 为 `ContinueErrorOutput` 时，常规输出之后的一个输出为 `"error"`。
+
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use alphaflow_nodes::node::{Backoff, Node, OnError, RetryPolicy};
+use alphaflow_nodes::node_type::MAIN_OUTPUT;
+use alphaflow_nodes::split_in_batches::{DONE_OUTPUT, LOOP_OUTPUT};
+use alphaflow_nodes::NodeRegistry;
+use crate::constants::{IF_NODE_TYPE, MERGE_NODE_TYPE, SPLIT_IN_BATCHES_NODE_TYPE, STICKY_NODE_TYPE, SWITCH_NODE_TYPE};
+use crate::workflow::{Connection, Workflow};
+
+/// n8n 内置节点类型的前缀
+pub const N8N_NODE_TYPE_PREFIX: &str = "n8n-nodes-base.";
+/// 本项目内置节点类型的前缀
+pub const AF_NODE_TYPE_PREFIX: &str = "af-nodes-base.";
+
+/// 名称与 n8n 不同的节点类型：(n8n 类型, 本项目类型)
+const RENAMED_NODE_TYPES: [(&str, &str); 1] = [("n8n-nodes-base.httpRequest", "http")];
+
+/// 错误输出的名称
+const ERROR_OUTPUT: &str = "error";
+
+/// 一个源节点的连接：连接类型（通常为 "main"）-> 按输出下标排列的目标列表
+pub type N8nNodeConnections = BTreeMap<String, Vec<Option<Vec<N8nConnection>>>>;
+
+/// n8n 导出的工作流
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct N8nWorkflow {
+    /// 旧版本 n8n 使用数字 ID，新版本使用字符串
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<N8nNode>,
+    /// 源节点名称 -> 该节点的连接
+    #[serde(default)]
+    pub connections: BTreeMap<String, N8nNodeConnections>,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub settings: Value,
+    /// 节点名称 -> Pin 数据项，每项形如 `{"json": {...}}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pin_data: BTreeMap<String, Vec<Value>>,
+}
+
+/// n8n 导出中的一个节点
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct N8nNode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    #[serde(default = "default_type_version")]
+    pub type_version: Value,
+    #[serde(default)]
+    pub position: [f64; 2],
+    #[serde(default)]
+    pub parameters: Value,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// "stopWorkflow"、"continueRegularOutput" 或 "continueErrorOutput"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<String>,
+    /// 旧版本 n8n 的 "失败后继续"，等同于 continueRegularOutput
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub continue_on_fail: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub retry_on_fail: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tries: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_between_tries: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<Value>,
+}
+
+/// 一条 n8n 连接的目标端
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct N8nConnection {
+    /// 目标节点名称
+    pub node: String,
+    /// 连接类型，通常为 "main"
+    #[serde(rename = "type", default = "main_output")]
+    pub connection_type: String,
+    /// 目标节点的输入下标
+    #[serde(default)]
+    pub index: usize,
+}
+
+fn default_type_version() -> Value {
+    json!(1)
+}
+
+fn main_output() -> String {
+    MAIN_OUTPUT.to_string()
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// 导入或导出时的错误
+#[derive(Debug, thiserror::Error)]
+pub enum N8nError {
+    #[error("Invalid n8n workflow JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+
+    #[error("Invalid n8n workflow: {0}")]
+    Invalid(String),
+}
+
+/// 无法在本项目中执行的 n8n 节点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsupportedNode {
+    /// 节点名称
+    pub name: String,
+    /// 原始的 n8n 节点类型
+    pub n8n_type: String,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct N8nImport {
+    pub workflow: Workflow,
+    /// 对应类型未注册的节点，按名称排序
+    pub unsupported_nodes: Vec<UnsupportedNode>,
+    /// 转换过程中丢弃或需要人工检查的内容
+    pub warnings: Vec<String>,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct N8nExport {
+    pub workflow: N8nWorkflow,
+    /// 转换过程中丢弃或需要人工检查的内容
+    pub warnings: Vec<String>,
+}
+
+/// 解析 n8n 导出的 JSON 文本并转换为 [`Workflow`]
+pub fn import_n8n_json(text: &str, registry: &NodeRegistry) -> Result<N8nImport, N8nError> {
+    let n8n: N8nWorkflow = serde_json::from_str(text)?;
+    import_n8n_workflow(&n8n, registry)
+}
+
+/// 把 [`Workflow`] 导出为 n8n JSON 文本
+pub fn export_n8n_json(wf: &Workflow) -> Result<(String, Vec<String>), N8nError> {
+    let export = export_n8n_workflow(wf);
+    Ok((serde_json::to_string_pretty(&export.workflow)?, export.warnings))
+}
+
+/// n8n 节点类型 -> 本项目节点类型
+pub fn from_n8n_node_type(n8n_type: &str) -> String {
+    if let Some((_, af)) = RENAMED_NODE_TYPES.iter().find(|(n8n, _)| *n8n == n8n_type) {
+        return af.to_string();
+    }
+    match n8n_type.strip_prefix(N8N_NODE_TYPE_PREFIX) {
+        Some(name) => format!("{}{}", AF_NODE_TYPE_PREFIX, name),
+        None => n8n_type.to_string(),
+    }
+}
+
+/// 本项目节点类型 -> n8n 节点类型
+pub fn to_n8n_node_type(node_type: &str) -> String {
+    if let Some((n8n, _)) = RENAMED_NODE_TYPES.iter().find(|(_, af)| *af == node_type) {
+        return n8n.to_string();
+    }
+    match node_type.strip_prefix(AF_NODE_TYPE_PREFIX) {
+        Some(name) => format!("{}{}", N8N_NODE_TYPE_PREFIX, name),
+        None => node_type.to_string(),
+    }
+}
+
+/// 导出时使用的 n8n 节点版本，参数转换按这些版本的格式进行
+pub fn type_version(node_type: &str) -> Value {
+    match node_type {
+        "http" => json!(4.2),
+        t if t == IF_NODE_TYPE => json!(2),
+        t if t == SWITCH_NODE_TYPE => json!(3),
+        t if t == MERGE_NODE_TYPE => json!(3),
+        t if t == SPLIT_IN_BATCHES_NODE_TYPE => json!(3),
+        _ => default_type_version(),
+    }
+}
+
+/// 把 n8n 工作流转换为 [`Workflow`]
+pub fn import_n8n_workflow(n8n: &N8nWorkflow, registry: &NodeRegistry) -> Result<N8nImport, N8nError> {
+    let mut warnings = Vec::new();
+    let mut unsupported_nodes = Vec::new();
+
+    let mut wf = Workflow::new(n8n.id.as_ref().map(id_to_string)).with_name(&n8n.name);
+    wf.active = n8n.active;
+    if n8n.settings.is_object() {
+        wf.settings = n8n.settings.clone();
+    }
+
+    for n8n_node in &n8n.nodes {
+        if n8n_node.name.trim().is_empty() {
+            return Err(N8nError::Invalid("Node with an empty name".to_owned()));
+        }
+        if wf.nodes.contains_key(&n8n_node.name) {
+            return Err(N8nError::Invalid(format!("Duplicate node name '{}'", n8n_node.name)));
+        }
+        let node_type = from_n8n_node_type(&n8n_node.node_type);
+        if node_type == STICKY_NODE_TYPE {
+            warnings.push(format!("Sticky note '{}' was skipped", n8n_node.name));
+            continue;
+        }
+        let registered = registry.get(&node_type).is_some();
+        if !registered {
+            unsupported_nodes.push(UnsupportedNode {
+                name: n8n_node.name.clone(),
+                n8n_type: n8n_node.node_type.clone(),
+            });
+        }
+        let node = import_node(n8n_node, node_type, registered, &mut warnings);
+        wf.add_node(node);
+    }
+
+    // 目标节点 -> (输入下标, 源节点, 输出)，用于恢复父节点顺序
+    let mut inputs: HashMap<String, Vec<(usize, String, String)>> = HashMap::new();
+    for (source, by_type) in &n8n.connections {
+        let Some(source_node) = wf.nodes.get(source) else {
+            if n8n.nodes.iter().any(|n| &n.name == source) {
+                continue; // 被忽略的便签节点
+            }
+            warnings.push(format!("Connections from unknown node '{}' were skipped", source));
+            continue;
+        };
+        for (connection_type, outputs) in by_type {
+            if connection_type != MAIN_OUTPUT {
+                warnings.push(format!(
+                    "Connections of type '{}' from '{}' are not supported and were skipped",
+                    connection_type, source
+                ));
+                continue;
+            }
+            for (index, targets) in outputs.iter().enumerate() {
+                let targets = targets.as_deref().unwrap_or_default();
+                if targets.is_empty() {
+                    continue;
+                }
+                let output = output_name(source_node, index, outputs.len());
+                for target in targets {
+                    if !wf.nodes.contains_key(&target.node) {
+                        warnings.push(format!(
+                            "Connection {} -> {} points to an unknown node and was skipped",
+                            source, target.node
+                        ));
+                        continue;
+                    }
+                    if target.connection_type != MAIN_OUTPUT {
+                        warnings.push(format!(
+                            "Connection {} -> {} of type '{}' is not supported and was skipped",
+                            source, target.node, target.connection_type
+                        ));
+                        continue;
+                    }
+                    wf.connections_by_source
+                        .entry(source.clone())
+                        .or_default()
+                        .push(Connection::new(&target.node, &output));
+                    inputs
+                        .entry(target.node.clone())
+                        .or_default()
+                        .push((target.index, source.clone(), output.clone()));
+                }
+            }
+        }
+    }
+    for (target, mut parents) in inputs {
+        parents.sort_by_key(|(index, _, _)| *index);
+        wf.connections_by_destination.insert(
+            target,
+            parents.iter().map(|(_, source, output)| Connection::new(source, output)).collect(),
+        );
+    }
+
+    for (node, items) in &n8n.pin_data {
+        if !wf.nodes.contains_key(node) {
+            warnings.push(format!("Pin data of unknown node '{}' was skipped", node));
+            continue;
+        }
+        let data = items
+            .iter()
+            .map(|item| item.get("json").cloned().unwrap_or_else(|| item.clone()))
+            .collect();
+        wf.pin_data.insert(node.clone(), Value::Array(data));
+    }
+
+    unsupported_nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(N8nImport {
+        workflow: wf,
+        unsupported_nodes,
+        warnings,
+    })
+}
+
+/// 把 [`Workflow`] 转换为 n8n 工作流
+pub fn export_n8n_workflow(wf: &Workflow) -> N8nExport {
+    let mut warnings = Vec::new();
+    let mut names: Vec<&String> = wf.nodes.keys().collect();
+    names.sort();
+
+    let nodes = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| export_node(&wf.nodes[*name], i, &mut warnings))
+        .collect();
+
+    let mut connections: BTreeMap<String, N8nNodeConnections> = BTreeMap::new();
+    for name in &names {
+        let Some(targets) = wf.connections_by_source.get(*name) else {
+            continue;
+        };
+        let node = &wf.nodes[*name];
+        let mut outputs: Vec<Option<Vec<N8nConnection>>> = Vec::new();
+        for conn in targets {
+            let Some(index) = output_index(node, &conn.output, targets) else {
+                warnings.push(format!(
+                    "Output '{}' of node '{}' has no n8n equivalent; connection to '{}' was skipped",
+                    conn.output, name, conn.node
+                ));
+                continue;
+            };
+            let input = match wf.nodes.get(&conn.node) {
+                Some(target) if target.node_type_name == MERGE_NODE_TYPE => wf
+                    .connections_by_destination
+                    .get(&conn.node)
+                    .and_then(|parents| {
+                        parents.iter().position(|p| p.node == **name && p.output == conn.output)
+                    })
+                    .unwrap_or(0),
+                _ => 0,
+            };
+            if outputs.len() <= index {
+                outputs.resize(index + 1, Some(Vec::new()));
+            }
+            outputs[index].get_or_insert_with(Vec::new).push(N8nConnection {
+                node: conn.node.clone(),
+                connection_type: MAIN_OUTPUT.to_string(),
+                index: input,
+            });
+        }
+        if !outputs.is_empty() {
+            connections.insert((*name).clone(), BTreeMap::from([(MAIN_OUTPUT.to_string(), outputs)]));
+        }
+    }
+
+    let pin_data = wf
+        .pin_data
+        .iter()
+        .map(|(node, data)| {
+            let items = match data {
+                Value::Array(values) => values.iter().map(|v| json!({ "json": v })).collect(),
+                Value::Null => Vec::new(),
+                other => vec![json!({ "json": other })],
+            };
+            (node.clone(), items)
+        })
+        .collect();
+
+    N8nExport {
+        workflow: N8nWorkflow {
+            id: wf.id.clone().map(Value::String),
+            name: wf.name.clone(),
+            nodes,
+            connections,
+            active: wf.active,
+            settings: wf.settings.clone(),
+            pin_data,
+        },
+        warnings,
+    }
+}
+
+fn id_to_string(id: &Value) -> String {
+    match id {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 转换单个节点；`registered` 为 false 时不生成执行参数
+fn import_node(n8n_node: &N8nNode, node_type: String, registered: bool, warnings: &mut Vec<String>) -> Node {
+    let mut node = Node::new(&n8n_node.name, &node_type)
+        .with_parameters(n8n_node.parameters.clone())
+        .with_position(n8n_node.position[0].round() as i64, n8n_node.position[1].round() as i64);
+    node.disabled = n8n_node.disabled;
+    node.description = n8n_node.notes.clone();
+    node.on_error = match n8n_node.on_error.as_deref() {
+        Some("continueRegularOutput") => OnError::ContinueRegularOutput,
+        Some("continueErrorOutput") => OnError::ContinueErrorOutput,
+        _ if n8n_node.continue_on_fail => OnError::ContinueRegularOutput,
+        _ => OnError::StopWorkflow,
+    };
+    if n8n_node.retry_on_fail {
+        node.retry_policy = Some(
+            RetryPolicy::new(n8n_node.max_tries.unwrap_or(3)).with_backoff(Backoff::Fixed {
+                delay_ms: n8n_node.wait_between_tries.unwrap_or(1000),
+            }),
+        );
+    }
+    if n8n_node.credentials.is_some() {
+        warnings.push(format!("Credentials of node '{}' were not imported", n8n_node.name));
+    }
+
+    if registered {
+        node.custom_config = match node_type.as_str() {
+            "http" => Some(import_http_parameters(n8n_node, warnings)),
+            t if t == IF_NODE_TYPE || t == SWITCH_NODE_TYPE => {
+                warnings.push(format!(
+                    "Conditions of node '{}' must be rewritten as JMESPath expressions",
+                    n8n_node.name
+                ));
+                None
+            }
+            t if t == MERGE_NODE_TYPE => import_merge_parameters(n8n_node, warnings),
+            // n8n 3.x 版本的 batchSize 默认为 1
+            t if t == SPLIT_IN_BATCHES_NODE_TYPE => Some(json!({
+                "batch_size": n8n_node.parameters.get("batchSize").cloned().unwrap_or(json!(1)),
+            })),
+            _ => Some(n8n_node.parameters.clone()),
+        };
+    }
+    node
+}
+
+fn import_http_parameters(n8n_node: &N8nNode, warnings: &mut Vec<String>) -> Value {
+    let params = &n8n_node.parameters;
+    let method = params
+        .get("method")
+        .or_else(|| params.get("requestMethod"))
+        .and_then(Value::as_str)
+        .unwrap_or("GET");
+    for option in ["sendQuery", "sendHeaders", "sendBody"] {
+        if params.get(option).and_then(Value::as_bool).unwrap_or(false) {
+            warnings.push(format!("Option '{}' of node '{}' is not supported", option, n8n_node.name));
+        }
+    }
+    json!({ "url": params.get("url").cloned().unwrap_or(json!("")), "method": method })
+}
+
+fn import_merge_parameters(n8n_node: &N8nNode, warnings: &mut Vec<String>) -> Option<Value> {
+    let params = &n8n_node.parameters;
+    let str_param = |key: &str| params.get(key).and_then(Value::as_str);
+    let combine_by = str_param("combineBy").or_else(|| str_param("combinationMode"));
+
+    let config = match (str_param("mode").unwrap_or("append"), combine_by) {
+        ("append", _) => json!({ "mode": "append" }),
+        ("chooseBranch", _) => json!({ "mode": "pick_first" }),
+        ("mergeByIndex", _) | ("combine", Some("combineByPosition" | "mergeByPosition")) => {
+            json!({ "mode": "merge_by_position" })
+        }
+        ("combine", Some("combineByFields" | "mergeByFields")) => {
+            let mode = match str_param("joinMode").unwrap_or("keepMatches") {
+                "keepMatches" => "inner_join",
+                "enrichInput1" => "left_join",
+                other => {
+                    warnings.push(format!(
+                        "Join mode '{}' of node '{}' is not supported",
+                        other, n8n_node.name
+                    ));
+                    return None;
+                }
+            };
+            let fields = params
+                .pointer("/mergeByFields/values/0")
+                .map(|pair| (pair.get("field1"), pair.get("field2")));
+            match (str_param("fieldsToMatchString"), fields) {
+                (Some(key), _) if !params["advanced"].as_bool().unwrap_or(false) => {
+                    json!({ "mode": mode, "key": key.trim() })
+                }
+                (_, Some((Some(left), Some(right)))) => {
+                    json!({ "mode": mode, "left_key": left, "right_key": right })
+                }
+                _ => {
+                    warnings.push(format!("Fields to match of node '{}' are missing", n8n_node.name));
+                    return None;
+                }
+            }
+        }
+        (mode, combine_by) => {
+            warnings.push(format!(
+                "Merge mode '{}' of node '{}' is not supported",
+                combine_by.unwrap_or(mode),
+                n8n_node.name
+            ));
+            return None;
+        }
+    };
+    Some(config)
+}
+
+fn export_node(node: &Node, position: usize, warnings: &mut Vec<String>) -> N8nNode {
+    let [x, y] = node.position.unwrap_or([position as i64 * 220, 300]);
+    let converted = match (node.node_type_name.as_str(), &node.custom_config) {
+        ("http", Some(config)) => Some(json!({
+            "method": config.get("method").cloned().unwrap_or(json!("GET")),
+            "url": config.get("url").cloned().unwrap_or(json!("")),
+        })),
+        (t, Some(config)) if t == MERGE_NODE_TYPE => export_merge_parameters(&node.name, config, warnings),
+        (t, Some(config)) if t == SPLIT_IN_BATCHES_NODE_TYPE => {
+            config.get("batch_size").map(|size| json!({ "batchSize": size }))
+        }
+        (t, Some(_)) if (t == IF_NODE_TYPE || t == SWITCH_NODE_TYPE) && node.parameters.is_null() => {
+            warnings.push(format!(
+                "Conditions of node '{}' cannot be converted to n8n and were left empty",
+                node.name
+            ));
+            Some(json!({}))
+        }
+        _ => None,
+    };
+    // 转换得到的参数覆盖导入时保留的原始参数
+    let parameters = match (converted, &node.parameters) {
+        (Some(Value::Object(converted)), Value::Object(original)) => {
+            let mut merged: Map<String, Value> = original.clone();
+            merged.extend(converted);
+            Value::Object(merged)
+        }
+        (Some(converted), _) => converted,
+        (None, Value::Null) => node.custom_config.clone().unwrap_or_else(|| json!({})),
+        (None, original) => original.clone(),
+    };
+
+    let (retry_on_fail, max_tries, wait_between_tries) = match &node.retry_policy {
+        Some(policy) => {
+            if !matches!(policy.backoff, Backoff::Fixed { .. }) || policy.jitter_ms > 0 {
+                warnings.push(format!(
+                    "Retry backoff of node '{}' was exported as a fixed delay",
+                    node.name
+                ));
+            }
+            (true, Some(policy.max_attempts), Some(policy.base_delay(1).as_millis() as u64))
+        }
+        None => (false, None, None),
+    };
+
+    N8nNode {
+        id: None,
+        name: node.name.clone(),
+        node_type: to_n8n_node_type(&node.node_type_name),
+        type_version: type_version(&node.node_type_name),
+        position: [x as f64, y as f64],
+        parameters,
+        disabled: node.disabled,
+        notes: node.description.clone(),
+        on_error: match node.on_error {
+            OnError::StopWorkflow => None,
+            OnError::ContinueRegularOutput => Some("continueRegularOutput".to_owned()),
+            OnError::ContinueErrorOutput => Some("continueErrorOutput".to_owned()),
+        },
+        continue_on_fail: false,
+        retry_on_fail,
+        max_tries,
+        wait_between_tries,
+        credentials: None,
+    }
+}
+
+fn export_merge_parameters(name: &str, config: &Value, warnings: &mut Vec<String>) -> Option<Value> {
+    let key = config.get("key").and_then(Value::as_str);
+    let left = config.get("left_key").and_then(Value::as_str).or(key);
+    let right = config.get("right_key").and_then(Value::as_str).or(key);
+    let params = match config.get("mode").and_then(Value::as_str).unwrap_or("append") {
+        "append" => json!({ "mode": "append" }),
+        "pick_first" => json!({ "mode": "chooseBranch" }),
+        "merge_by_position" => json!({ "mode": "combine", "combineBy": "combineByPosition" }),
+        mode @ ("inner_join" | "left_join") => {
+            let join_mode = if mode == "inner_join" { "keepMatches" } else { "enrichInput1" };
+            match (left, right) {
+                (Some(l), Some(r)) if l == r => json!({
+                    "mode": "combine",
+                    "combineBy": "combineByFields",
+                    "fieldsToMatchString": l,
+                    "joinMode": join_mode,
+                }),
+                (Some(l), Some(r)) => json!({
+                    "mode": "combine",
+                    "combineBy": "combineByFields",
+                    "advanced": true,
+                    "mergeByFields": { "values": [{ "field1": l, "field2": r }] },
+                    "joinMode": join_mode,
+                }),
+                _ => return None,
+            }
+        }
+        other => {
+            warnings.push(format!("Merge mode '{}' of node '{}' has no n8n equivalent", other, name));
+            return None;
+        }
+    };
+    Some(params)
+}
+
+/// 节点的常规输出名称，按 n8n 输出下标排列；为空表示按下标命名
+fn regular_outputs(node: &Node) -> Option<Vec<String>> {
+    match node.node_type_name.as_str() {
+        t if t == IF_NODE_TYPE => Some(vec!["true".to_owned(), "false".to_owned()]),
+        t if t == SPLIT_IN_BATCHES_NODE_TYPE => Some(vec![DONE_OUTPUT.to_owned(), LOOP_OUTPUT.to_owned()]),
+        t if t == SWITCH_NODE_TYPE => {
+            let config = node.custom_config.as_ref()?;
+            let mut outputs: Vec<String> = config
+                .get("rules")?
+                .as_array()?
+                .iter()
+                .filter_map(|rule| rule.get("output").and_then(Value::as_str).map(str::to_owned))
+                .collect();
+            if let Some(fallback) = config.get("fallback_output").and_then(Value::as_str) {
+                outputs.push(fallback.to_owned());
+            }
+            Some(outputs)
+        }
+        _ => Some(vec![MAIN_OUTPUT.to_owned()]),
+    }
+}
+
+/// n8n 输出下标 -> 输出名称；`output_count` 为该节点在 n8n 连接中的输出数量
+fn output_name(node: &Node, index: usize, output_count: usize) -> String {
+    let regular = regular_outputs(node);
+    let error_index = match &regular {
+        Some(outputs) => outputs.len(),
+        None => output_count.saturating_sub(1),
+    };
+    if node.on_error == OnError::ContinueErrorOutput && index == error_index {
+        return ERROR_OUTPUT.to_owned();
+    }
+    match regular.and_then(|outputs| outputs.get(index).cloned()) {
+        Some(name) => name,
+        None => index.to_string(),
+    }
+}
+
+/// 输出名称 -> n8n 输出下标；`connections` 为该节点的全部连接
+fn output_index(node: &Node, output: &str, connections: &[Connection]) -> Option<usize> {
+    let regular = regular_outputs(node);
+    if let Some(index) = regular.as_ref().and_then(|outputs| outputs.iter().position(|o| o == output)) {
+        return Some(index);
+    }
+    if output == ERROR_OUTPUT {
+        return Some(match regular {
+            Some(outputs) => outputs.len(),
+            None => connections
+                .iter()
+                .filter_map(|c| c.output.parse::<usize>().ok())
+                .max()
+                .map_or(0, |max| max + 1),
+        });
+    }
+    output.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alphaflow_nodes::registry_helper::register_all_nodes;
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        register_all_nodes(&mut registry);
+        registry
+    }
+
+    fn sample_export() -> &'static str {
+        r##"{
+            "id": 7,
+            "name": "Sync orders",
+            "active": true,
+            "settings": { "executionOrder": "v1" },
+            "nodes": [
+                { "id": "a1", "name": "Start", "type": "n8n-nodes-base.manualTrigger",
+                  "typeVersion": 1, "position": [0, 300], "parameters": {} },
+                { "name": "Fetch", "type": "n8n-nodes-base.httpRequest", "typeVersion": 4.2,
+                  "position": [220, 300], "retryOnFail": true, "maxTries": 2, "waitBetweenTries": 500,
+                  "onError": "continueErrorOutput",
+                  "parameters": { "method": "POST", "url": "https://example.com/orders", "options": {} } },
+                { "name": "Check", "type": "n8n-nodes-base.if", "typeVersion": 2, "position": [440, 300],
+                  "parameters": { "conditions": { "conditions": [] } } },
+                { "name": "Join", "type": "n8n-nodes-base.merge", "typeVersion": 3, "position": [660, 300],
+                  "parameters": { "mode": "combine", "combineBy": "combineByFields",
+                                  "fieldsToMatchString": "id", "joinMode": "enrichInput1" } },
+                { "name": "Report", "type": "n8n-nodes-base.slack", "typeVersion": 2, "position": [880, 300],
+                  "parameters": { "channel": "#orders" }, "credentials": { "slackApi": { "id": "1" } } },
+                { "name": "Note", "type": "n8n-nodes-base.stickyNote", "typeVersion": 1,
+                  "position": [0, 0], "parameters": { "content": "hi" } }
+            ],
+            "connections": {
+                "Start": { "main": [[{ "node": "Fetch", "type": "main", "index": 0 }]] },
+                "Fetch": { "main": [
+                    [{ "node": "Check", "type": "main", "index": 0 }],
+                    [{ "node": "Report", "type": "main", "index": 0 }]
+                ] },
+                "Check": { "main": [
+                    [{ "node": "Join", "type": "main", "index": 1 }],
+                    [{ "node": "Join", "type": "main", "index": 0 }]
+                ] }
+            },
+            "pinData": { "Start": [{ "json": { "since": "2024-01-01" } }] }
+        }"##
+    }
+
+    #[test]
+    fn test_import_n8n_workflow() {
+        let import = import_n8n_json(sample_export(), &registry()).unwrap();
+        let wf = &import.workflow;
+
+        assert_eq!(wf.id.as_deref(), Some("7"));
+        assert_eq!(wf.name, "Sync orders");
+        assert!(wf.active);
+        assert!(!wf.nodes.contains_key("Note"));
+
+        let fetch = &wf.nodes["Fetch"];
+        assert_eq!(fetch.node_type_name, "http");
+        assert_eq!(fetch.position, Some([220, 300]));
+        assert_eq!(fetch.on_error, OnError::ContinueErrorOutput);
+        assert_eq!(fetch.retry_policy.as_ref().unwrap().max_attempts, 2);
+        assert_eq!(
+            fetch.custom_config,
+            Some(json!({ "url": "https://example.com/orders", "method": "POST" }))
+        );
+        assert_eq!(
+            wf.nodes["Join"].custom_config,
+            Some(json!({ "mode": "left_join", "key": "id" }))
+        );
+        assert_eq!(wf.nodes["Check"].custom_config, None);
+
+        assert_eq!(wf.connections_by_source["Fetch"], vec![
+            Connection::new("Check", "main"),
+            Connection::new("Report", "error"),
+        ]);
+        // Join 的父节点按 n8n 输入下标排序
+        assert_eq!(wf.connections_by_destination["Join"], vec![
+            Connection::new("Check", "false"),
+            Connection::new("Check", "true"),
+        ]);
+        assert_eq!(wf.pin_data["Start"], json!([{ "since": "2024-01-01" }]));
+
+        let unsupported: Vec<&str> = import.unsupported_nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(unsupported, vec!["Report", "Start"]);
+        assert_eq!(wf.nodes["Report"].node_type_name, "af-nodes-base.slack");
+        assert!(import.warnings.iter().any(|w| w.contains("Credentials of node 'Report'")));
+        assert!(import.warnings.iter().any(|w| w.contains("Sticky note 'Note'")));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let import = import_n8n_json(sample_export(), &registry()).unwrap();
+        let export = export_n8n_workflow(&import.workflow);
+        let n8n = &export.workflow;
+
+        let fetch = n8n.nodes.iter().find(|n| n.name == "Fetch").unwrap();
+        assert_eq!(fetch.node_type, "n8n-nodes-base.httpRequest");
+        assert_eq!(fetch.position, [220.0, 300.0]);
+        assert_eq!(fetch.parameters["options"], json!({}));
+        assert_eq!(fetch.on_error.as_deref(), Some("continueErrorOutput"));
+        assert_eq!((fetch.max_tries, fetch.wait_between_tries), (Some(2), Some(500)));
+
+        let report = n8n.nodes.iter().find(|n| n.name == "Report").unwrap();
+        assert_eq!(report.node_type, "n8n-nodes-base.slack");
+
+        let original: N8nWorkflow = serde_json::from_str(sample_export()).unwrap();
+        assert_eq!(n8n.connections, original.connections);
+        assert_eq!(n8n.pin_data, original.pin_data);
+
+        // 再次导入得到相同的工作流
+        let again = import_n8n_workflow(n8n, &registry()).unwrap();
+        assert_eq!(again.workflow, import.workflow);
+    }
+
+    #[test]
+    fn test_export_named_outputs() {
+        let mut wf = Workflow::new(None).with_name("routing");
+        wf.add_node(Node::new("route", SWITCH_NODE_TYPE).with_custom_config(json!({
+            "rules": [
+                { "output": "big", "condition": "amount > `100`" },
+                { "output": "small", "condition": "amount <= `100`" }
+            ],
+            "fallback_output": "other"
+        })));
+        for name in ["a", "b", "c"] {
+            wf.add_node(Node::new(name, MERGE_NODE_TYPE));
+        }
+        wf.connect_nodes_on_output("route", "other", "c").unwrap();
+        wf.connect_nodes_on_output("route", "small", "b").unwrap();
+        wf.connect_nodes_on_output("route", "big", "a").unwrap();
+        wf.connect_nodes_on_output("route", "unknown", "a").unwrap();
+
+        let export = export_n8n_workflow(&wf);
+        let outputs = &export.workflow.connections["route"]["main"];
+        let targets: Vec<Vec<&str>> = outputs
+            .iter()
+            .map(|o| o.iter().flatten().map(|c| c.node.as_str()).collect())
+            .collect();
+        assert_eq!(targets, vec![vec!["a"], vec!["b"], vec!["c"]]);
+        assert_eq!(export.warnings.len(), 2);
+        assert!(export.warnings.iter().any(|w| w.contains("Output 'unknown'")));
+    }
+}
@@ -0,0 +1,782 @@
+// src/persistence.rs
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use chrono::Utc;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use alphaflow_nodes::node_type::{NodeOutput, MAIN_OUTPUT};
+use alphaflow_nodes::static_data::StaticData;
+use alphaflow_nodes::NodeRegistry;
+use alphaflow_sqlite::db::execution_ops;
+use alphaflow_sqlite::models::execution::{Execution, NewExecution};
+use crate::executor::{execute_in_session, ExecutionOptions};
+use crate::run_result::{NodeRunRecord, RunError, RunResult, WaitingState};
+use crate::storage;
+use crate::workflow::{invalid_run, Workflow};
+
+/// SQLite 连接池
+pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+/// 运行的触发方式，对应 executions 表的 `mode` 列
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// 用户手动运行
+    #[default]
+    Manual,
+    /// 由触发器启动
+    Trigger,
+    /// 重新运行之前失败的执行
+    Retry,
+}
+
+impl ExecutionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionMode::Manual => "manual",
+            ExecutionMode::Trigger => "trigger",
+            ExecutionMode::Retry => "retry",
+        }
+    }
+}
+
+/// 单个节点的执行数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NodeExecutionData {
+    /// 节点执行时的输入
+    #[serde(default)]
+    pub input: Option<Value>,
+    /// 节点输出
+    #[serde(default)]
+    pub output: Option<Value>,
+    /// 执行尝试、错误等记录
+    #[serde(flatten)]
+    pub run: NodeRunRecord,
+}
+
+/// 保存在 executions 表 `data` 列中的执行数据（JSON）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecutionData {
+    /// 节点 ID -> 执行数据
+    pub nodes: HashMap<String, NodeExecutionData>,
+    /// 运行失败时的错误
+    #[serde(default)]
+    pub error: Option<RunError>,
+    /// 运行时的工作流定义，用于恢复执行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<Workflow>,
+    /// 运行在 Wait 节点处暂停时的等待信息，见 [`resume_waiting_execution`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting: Option<WaitingState>,
+}
+
+impl NodeExecutionData {
+    /// 节点是否已经成功执行（可以在恢复执行时沿用其输出）
+    pub fn is_completed(&self) -> bool {
+        self.output.is_some() && self.run.error.is_none()
+    }
+}
+
+/// 把运行记录写入 executions 表，通过 [`crate::executor::ExecutionOptions::with_recorder`] 启用
+///
+/// 写入失败只记录日志，不影响工作流运行。
+#[derive(Clone)]
+pub struct ExecutionRecorder {
+    pool: DbPool,
+    mode: ExecutionMode,
+    started_by_user_id: Option<String>,
+}
+
+impl std::fmt::Debug for ExecutionRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionRecorder")
+            .field("mode", &self.mode)
+            .field("started_by_user_id", &self.started_by_user_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExecutionRecorder {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            mode: ExecutionMode::Manual,
+            started_by_user_id: None,
+        }
+    }
+
+    /// 设置运行的触发方式
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置启动运行的用户
+    pub fn with_started_by(mut self, user_id: &str) -> Self {
+        self.started_by_user_id = Some(user_id.to_string());
+        self
+    }
+
+    /// 创建 executions 记录，失败时返回 None
+    pub(crate) async fn start(&self, workflow: &Workflow) -> Option<ExecutionSession> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let created = {
+            let (pool, exec_id, mode) = (self.pool.clone(), id.clone(), self.mode);
+            let workflow_id = workflow.id.clone().unwrap_or_default();
+            let started_by_user_id = self.started_by_user_id.clone();
+            blocking(move || {
+                let new_exec = NewExecution {
+                    id: Some(&exec_id),
+                    workflow_id: &workflow_id,
+                    finished: false,
+                    mode: mode.as_str(),
+                    started_at: Utc::now().naive_utc(),
+                    stopped_at: None,
+                    data: None,
+                    started_by_user_id: started_by_user_id.as_deref(),
+                };
+                let mut conn = pool.get().map_err(|e| e.to_string())?;
+                execution_ops::create_execution(&mut conn, &new_exec).map_err(|e| e.to_string())
+            })
+            .await
+        };
+        match created {
+            Ok(_) => Some(ExecutionSession::reopen(
+                self.pool.clone(),
+                &id,
+                ExecutionData {
+                    workflow: Some(workflow.clone()),
+                    ..Default::default()
+                },
+            )),
+            Err(e) => {
+                warn!("Failed to create execution record: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// 一次运行对应的 executions 记录，随执行进度更新
+///
+/// 节点事件只更新内存中的执行数据并通知后台的写入任务；写入任务在阻塞线程池中序列化最新的执行数据并写入，
+/// 来不及写入的多次更新合并为一次写入，调度器所在的线程既不序列化也不访问数据库。
+pub(crate) struct ExecutionSession {
+    pool: DbPool,
+    id: String,
+    data: Arc<Mutex<ExecutionData>>,
+    updates: mpsc::UnboundedSender<()>,
+    writer: JoinHandle<()>,
+}
+
+impl ExecutionSession {
+    /// 继续写入已有的执行记录（恢复暂停的执行时使用）
+    pub(crate) fn reopen(pool: DbPool, id: &str, data: ExecutionData) -> Self {
+        let data = Arc::new(Mutex::new(data));
+        let (updates, mut pending) = mpsc::unbounded_channel::<()>();
+        let writer = {
+            let (pool, id, data) = (pool.clone(), id.to_string(), data.clone());
+            tokio::spawn(async move {
+                while pending.recv().await.is_some() {
+                    while pending.try_recv().is_ok() {}
+                    let (pool, exec_id, data) = (pool.clone(), id.clone(), data.clone());
+                    let updated = blocking(move || {
+                        let data = serde_json::to_string(&*lock_data(&data)).map_err(|e| e.to_string())?;
+                        write_execution(&pool, &exec_id, &data, execution_ops::update_execution_data)
+                    })
+                    .await;
+                    if let Err(e) = updated {
+                        warn!("Failed to update execution record {}: {}", id, e);
+                    }
+                }
+            })
+        };
+        Self { pool, id: id.to_string(), data, updates, writer }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 节点开始执行
+    pub(crate) fn node_started(&mut self, node_id: &str, input: &Value) {
+        lock_data(&self.data).nodes.entry(node_id.to_string()).or_default().input = Some(input.clone());
+        self.save();
+    }
+
+    /// 节点执行结束（成功或失败）
+    pub(crate) fn node_finished(&mut self, node_id: &str, output: Option<&Value>, run: &NodeRunRecord) {
+        {
+            let mut data = lock_data(&self.data);
+            let node = data.nodes.entry(node_id.to_string()).or_default();
+            node.output = output.cloned();
+            node.run = run.clone();
+        }
+        self.save();
+    }
+
+    /// 沿用之前运行结果的节点；继续写入同一条执行记录时，已经成功的节点保留原有的执行记录
+    pub(crate) fn nodes_reused<'a>(
+        &mut self,
+        nodes: impl Iterator<Item = (&'a String, &'a Value, &'a NodeRunRecord)>,
+    ) {
+        {
+            let mut data = lock_data(&self.data);
+            for (node_id, output, run) in nodes {
+                let node = data.nodes.entry(node_id.clone()).or_default();
+                if node.is_completed() {
+                    continue;
+                }
+                node.output = Some(output.clone());
+                node.run = run.clone();
+            }
+        }
+        self.save();
+    }
+
+    /// 运行结束：等待尚未完成的写入后写入最终结果并设置 finished / stopped_at；
+    /// 运行在 Wait 节点处暂停时只记录恢复时间 wait_till，执行记录保持未结束
+    pub(crate) async fn finish(self, result: &RunResult) {
+        {
+            let mut data = lock_data(&self.data);
+            for (node_id, output) in &result.outputs {
+                data.nodes.entry(node_id.clone()).or_default().output = Some(output.clone());
+            }
+            for (node_id, run) in &result.node_runs {
+                let node = data.nodes.entry(node_id.clone()).or_default();
+                if !(run.reused && node.is_completed()) {
+                    node.run = run.clone();
+                }
+            }
+            data.error = result.error.clone();
+            data.waiting = result.waiting.clone();
+        }
+
+        let Self { pool, id, data, updates, writer } = self;
+        drop(updates);
+        // 写入任务只会因为 panic 失败，此时最终结果仍然照常写入
+        let _ = writer.await;
+
+        let (success, waiting) = (result.is_success(), result.waiting.clone());
+        let exec_id = id.clone();
+        let finished = blocking(move || {
+            let data = serde_json::to_string(&*lock_data(&data)).map_err(|e| e.to_string())?;
+            write_execution(&pool, &exec_id, &data, |conn, id, data| match &waiting {
+                Some(waiting) => execution_ops::set_execution_waiting(conn, id, waiting.wait_till.naive_utc(), data),
+                None => execution_ops::finish_execution(conn, id, success, Utc::now().naive_utc(), data),
+            })
+        })
+        .await;
+        if let Err(e) = finished {
+            warn!("Failed to finish execution record {}: {}", id, e);
+        }
+    }
+
+    /// 工作流在 workflows 表中保存的静态数据；工作流没有 ID、尚未保存或读取失败时返回 None
+    pub(crate) async fn load_static_data(&self, workflow: &Workflow) -> Option<StaticData> {
+        let wf_id = workflow.id.clone()?;
+        let (pool, id) = (self.pool.clone(), wf_id.clone());
+        let loaded = blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            match storage::load_static_data(&mut conn, &id) {
+                Ok(static_data) => Ok(Some(static_data)),
+                Err(storage::WorkflowRowError::Database(diesel::result::Error::NotFound)) => Ok(None),
+                Err(e) => Err(e.to_string()),
+            }
+        })
+        .await;
+        loaded.unwrap_or_else(|e| {
+            warn!("Failed to load static data of {}: {}", wf_id, e);
+            None
+        })
+    }
+
+    /// 把运行中修改过的静态数据写回 workflows 表
+    pub(crate) async fn save_static_data(&self, workflow: &Workflow, static_data: StaticData) {
+        let Some(wf_id) = workflow.id.clone() else {
+            return;
+        };
+        let (pool, id) = (self.pool.clone(), wf_id.clone());
+        let saved = blocking(move || {
+            let mut conn = pool.get().map_err(|e| e.to_string())?;
+            storage::save_static_data(&mut conn, &id, &static_data).map_err(|e| e.to_string())
+        })
+        .await;
+        if let Err(e) = saved {
+            warn!("Failed to save static data of {}: {}", wf_id, e);
+        }
+    }
+
+    /// 通知写入任务执行数据有更新
+    fn save(&self) {
+        if self.updates.send(()).is_err() {
+            warn!("Failed to update execution record {}: writer has stopped", self.id);
+        }
+    }
+}
+
+/// 写入任务与调度器共享的执行数据；持有锁期间不会 panic，忽略中毒状态
+fn lock_data(data: &Mutex<ExecutionData>) -> MutexGuard<'_, ExecutionData> {
+    data.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 在阻塞线程池中执行数据库操作
+async fn blocking<T, F>(op: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(op).await.unwrap_or_else(|e| Err(e.to_string()))
+}
+
+/// 在阻塞上下文中写入执行记录
+fn write_execution<F>(pool: &DbPool, id: &str, data: &str, op: F) -> Result<(), String>
+where
+    F: FnOnce(&mut SqliteConnection, &str, &str) -> diesel::QueryResult<usize>,
+{
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    op(&mut conn, id, data).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 读取执行记录及其执行数据
+pub fn load_execution(pool: &DbPool, execution_id: &str) -> Result<(Execution, ExecutionData), String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let execution = execution_ops::get_execution_by_id(&mut conn, execution_id)
+        .map_err(|e| format!("Execution {} not found: {}", execution_id, e))?;
+    let data = match execution.data.as_deref() {
+        Some(data) => serde_json::from_str(data)
+            .map_err(|e| format!("Invalid data of execution {}: {}", execution_id, e))?,
+        None => ExecutionData::default(),
+    };
+    Ok((execution, data))
+}
+
+/// 把仍标记为运行中、但在 `stale_after` 内没有任何更新的执行标记为已中断（设置 stopped_at），返回标记的数量。
+///
+/// 运行所在的进程崩溃或被终止时执行记录不会结束，标记之后才能通过 [`resume_execution`] / [`retry_execution`] 继续。
+/// 进程启动时（还没有开始任何运行）以 `Duration::ZERO` 调用可以标记上次进程遗留的全部执行；
+/// 运行期间调用时 `stale_after` 应大于单个节点的最长执行时间，否则仍在执行的运行也会被标记。
+pub fn mark_interrupted_executions(pool: &DbPool, stale_after: Duration) -> Result<usize, String> {
+    let now = Utc::now();
+    let stale_after = chrono::Duration::from_std(stale_after).map_err(|e| e.to_string())?;
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    execution_ops::mark_interrupted_executions(&mut conn, (now - stale_after).naive_utc(), now.naive_utc())
+        .map_err(|e| e.to_string())
+}
+
+/// 恢复失败或被中断（取消、超时）的执行：使用执行记录中保存的工作流定义，
+/// 已经成功的节点沿用之前的输出不再执行，失败的和尚未执行的节点重新执行。
+///
+/// 新的运行作为一条 `retry` 方式的执行记录写入同一个数据库。
+/// 仍在运行的执行和在 Wait 节点处暂停的执行（应使用 [`resume_waiting_execution`]）会被拒绝；
+/// 进程退出时遗留的运行中的执行需要先由 [`mark_interrupted_executions`] 标记为已中断。
+pub async fn resume_execution(
+    pool: &DbPool,
+    registry: &NodeRegistry,
+    execution_id: &str,
+    options: ExecutionOptions,
+) -> RunResult {
+    rerun_execution(pool, registry, execution_id, None, options).await
+}
+
+/// 与 [`resume_execution`] 相同，但使用最新的工作流定义（例如修复了失败节点的配置之后）；
+/// 新定义中已经不存在的节点的历史输出会被忽略。
+pub async fn retry_execution(
+    pool: &DbPool,
+    registry: &NodeRegistry,
+    execution_id: &str,
+    workflow: &Workflow,
+    options: ExecutionOptions,
+) -> RunResult {
+    rerun_execution(pool, registry, execution_id, Some(workflow), options).await
+}
+
+async fn rerun_execution(
+    pool: &DbPool,
+    registry: &NodeRegistry,
+    execution_id: &str,
+    workflow: Option<&Workflow>,
+    options: ExecutionOptions,
+) -> RunResult {
+    let (execution, data) = match load_execution(pool, execution_id) {
+        Ok(loaded) => loaded,
+        Err(message) => return invalid_run(message),
+    };
+    if execution.finished {
+        return invalid_run(format!("Execution {} already finished successfully", execution_id));
+    }
+    if data.waiting.is_some() {
+        return invalid_run(format!(
+            "Execution {} is waiting, use resume_waiting_execution to continue it",
+            execution_id
+        ));
+    }
+    if execution.stopped_at.is_none() {
+        return invalid_run(format!(
+            "Execution {} is still running; mark it with mark_interrupted_executions if its process has stopped",
+            execution_id
+        ));
+    }
+    let workflow = match workflow.or(data.workflow.as_ref()) {
+        Some(workflow) => workflow,
+        None => return invalid_run(format!("Execution {} has no workflow snapshot", execution_id)),
+    };
+
+    let seeds = completed_seeds(workflow, &data);
+    let recorder = options
+        .recorder
+        .clone()
+        .unwrap_or_else(|| ExecutionRecorder::new(pool.clone()))
+        .with_mode(ExecutionMode::Retry);
+    let options = options.with_recorder(recorder);
+    workflow.execute_seeded(registry, &options, seeds).await
+}
+
+/// 恢复在 Wait 节点处暂停的执行，继续写入同一条执行记录：
+/// 已经成功的节点沿用之前的输出，暂停的 Wait 节点以 `payload`（为空时为其输入）作为输出，
+/// 之后的节点照常执行。再次遇到需要长时间等待的 Wait 节点时，执行会再次暂停。
+///
+/// 运行使用 `options`（子工作流、环境变量白名单等），工作流快照 settings 中的字段覆盖对应的选项
+/// （见 [`ExecutionOptions::with_settings`]）。
+///
+/// 等待时间到期的执行通常由 [`crate::wait::enqueue_due_executions`] 交给任务引擎恢复；
+/// 等待外部恢复的执行（[`crate::wait::WaitParams::External`]）由调用方直接调用本函数。
+pub async fn resume_waiting_execution(
+    pool: &DbPool,
+    registry: &NodeRegistry,
+    execution_id: &str,
+    payload: Option<Value>,
+    options: ExecutionOptions,
+) -> RunResult {
+    let (execution, mut data) = match load_execution(pool, execution_id) {
+        Ok(loaded) => loaded,
+        Err(message) => return invalid_run(message),
+    };
+    let waiting = match data.waiting.take() {
+        Some(waiting) if !execution.finished => waiting,
+        _ => return invalid_run(format!("Execution {} is not waiting", execution_id)),
+    };
+    let Some(workflow) = data.workflow.clone() else {
+        return invalid_run(format!("Execution {} has no workflow snapshot", execution_id));
+    };
+
+    let mut seeds = completed_seeds(&workflow, &data);
+    for node_id in &waiting.nodes {
+        let input = data.nodes.get(node_id).and_then(|node| node.input.clone());
+        let output = payload.clone().or(input).unwrap_or_default();
+        seeds.insert(node_id.clone(), NodeOutput::new(output));
+    }
+    let options = options.with_settings(&workflow.settings);
+    let session = ExecutionSession::reopen(pool.clone(), execution_id, data);
+    execute_in_session(&workflow, registry, &options, seeds, session).await
+}
+
+/// 可以沿用的节点输出：只沿用所有祖先节点都已成功的节点，重新执行的节点的后代也需要重新执行
+fn completed_seeds(workflow: &Workflow, data: &ExecutionData) -> HashMap<String, NodeOutput> {
+    let graph = workflow.to_directed_graph();
+    let is_completed = |node_id: &String| {
+        workflow.nodes.contains_key(node_id)
+            && data.nodes.get(node_id).is_some_and(NodeExecutionData::is_completed)
+    };
+    data.nodes
+        .iter()
+        .filter(|(node_id, _)| is_completed(node_id))
+        .filter(|(node_id, _)| graph.get_all_parents(node_id).iter().all(is_completed))
+        .map(|(node_id, node)| {
+            let outputs = if node.run.outputs.is_empty() {
+                vec![MAIN_OUTPUT.to_string()]
+            } else {
+                node.run.outputs.clone()
+            };
+            let output = node.output.clone().unwrap_or_default();
+            (node_id.clone(), NodeOutput::on_outputs(output, outputs))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use alphaflow_nodes::node::Node;
+    use alphaflow_nodes::node_type::{NodeError, NodeExecutionContext, NodeType};
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    /// 测试用节点：参数 fail 为 true 时失败，否则返回 { "echo": <输入> }；
+    /// 参数 fail_once 为 true 的节点只有第一次执行失败。记录被执行的次数。
+    struct EchoNode(Arc<AtomicUsize>, AtomicBool);
+
+    #[async_trait]
+    impl NodeType for EchoNode {
+        fn name(&self) -> &str {
+            "echo"
+        }
+        fn display_name(&self) -> &str {
+            "Echo Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let fail_once =
+                ctx.parameters["fail_once"].as_bool().unwrap_or(false) && !self.1.swap(true, Ordering::SeqCst);
+            if fail_once || ctx.parameters["fail"].as_bool().unwrap_or(false) {
+                return Err(NodeError::ExecutionFailed("boom".into()));
+            }
+            Ok(NodeOutput::new(json!({ "echo": ctx.input_data })))
+        }
+    }
+
+    fn echo_registry(calls: Arc<AtomicUsize>) -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(EchoNode(calls, AtomicBool::new(false))));
+        registry
+    }
+
+    /// 测试用节点：把节点级静态数据 cursor 加 1 并输出，同时记录工作流级的运行次数
+    struct CursorNode;
+
+    #[async_trait]
+    impl NodeType for CursorNode {
+        fn name(&self) -> &str {
+            "cursor"
+        }
+        fn display_name(&self) -> &str {
+            "Cursor Node"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            let static_data = ctx.static_data.as_ref().expect("static data");
+            let cursor = static_data.get("cursor").and_then(|v| v.as_u64()).unwrap_or(0) + 1;
+            static_data.set("cursor", json!(cursor));
+            static_data.set_global("runs", json!(cursor));
+            Ok(NodeOutput::new(json!({ "cursor": cursor })))
+        }
+    }
+
+    fn memory_pool() -> DbPool {
+        // 内存数据库只在单个连接内可见，因此连接池只保留一个连接
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .unwrap();
+        alphaflow_sqlite::run_migrations(&mut pool.get().unwrap()).unwrap();
+        pool
+    }
+
+    fn echo_workflow(fail_second: bool) -> Workflow {
+        let mut wf = Workflow::new(Some("wf-1".into()));
+        wf.add_node(Node::new("first", "echo"));
+        wf.add_node(Node::new("second", "echo").with_custom_config(json!({ "fail": fail_second })));
+        wf.connect_nodes("first", "second").unwrap();
+        wf
+    }
+
+    async fn run_recorded(wf: &Workflow, recorder: ExecutionRecorder) -> (RunResult, Execution) {
+        let registry = echo_registry(Arc::new(AtomicUsize::new(0)));
+        let pool = recorder.pool.clone();
+        let options = ExecutionOptions::default().with_recorder(recorder);
+        let result = wf.execute_with_options(&registry, &options).await;
+        let id = result.execution_id.clone().expect("execution id");
+        let execution = execution_ops::get_execution_by_id(&mut pool.get().unwrap(), &id).unwrap();
+        (result, execution)
+    }
+
+    #[tokio::test]
+    async fn test_successful_run_is_recorded() {
+        let recorder = ExecutionRecorder::new(memory_pool()).with_mode(ExecutionMode::Trigger);
+        let (result, execution) = run_recorded(&echo_workflow(false), recorder).await;
+        assert!(result.is_success());
+        assert_eq!(execution.workflow_id, "wf-1");
+        assert_eq!(execution.mode, "trigger");
+        assert!(execution.finished);
+        assert!(execution.stopped_at.is_some());
+
+        let data: ExecutionData = serde_json::from_str(execution.data.as_deref().unwrap()).unwrap();
+        let second = &data.nodes["second"];
+        assert_eq!(second.input, Some(json!({ "echo": {} })));
+        assert_eq!(second.output, Some(json!({ "echo": { "echo": {} } })));
+        assert_eq!(second.run.attempts.len(), 1);
+        assert!(data.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_run_is_recorded() {
+        let recorder = ExecutionRecorder::new(memory_pool()).with_started_by("user-1");
+        let (result, execution) = run_recorded(&echo_workflow(true), recorder).await;
+        assert!(!result.is_success());
+        assert_eq!(execution.mode, "manual");
+        assert_eq!(execution.started_by_user_id.as_deref(), Some("user-1"));
+        assert!(!execution.finished);
+        assert!(execution.stopped_at.is_some());
+
+        let data: ExecutionData = serde_json::from_str(execution.data.as_deref().unwrap()).unwrap();
+        assert_eq!(data.error, result.error);
+        assert!(data.nodes["first"].output.is_some());
+        assert!(data.nodes["second"].output.is_none());
+        assert_eq!(data.nodes["second"].run.error.as_ref().unwrap().message, "boom");
+    }
+
+    #[tokio::test]
+    async fn test_static_data_persists_between_runs() {
+        let pool = memory_pool();
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(CursorNode));
+        let mut wf = Workflow::new(Some("wf-1".into()));
+        wf.add_node(Node::new("poll", "cursor"));
+        storage::save_workflow(&mut pool.get().unwrap(), &mut wf).unwrap();
+
+        let options = ExecutionOptions::default().with_recorder(ExecutionRecorder::new(pool.clone()));
+        let first = wf.execute_with_options(&registry, &options).await;
+        assert_eq!(first.outputs["poll"], json!({ "cursor": 1 }));
+        let second = wf.execute_with_options(&registry, &options).await;
+        assert_eq!(second.outputs["poll"], json!({ "cursor": 2 }), "reads the data saved by the first run");
+
+        let saved = storage::load_static_data(&mut pool.get().unwrap(), "wf-1").unwrap();
+        assert_eq!(saved.nodes["poll"]["cursor"], json!(2));
+        assert_eq!(saved.global["runs"], json!(2));
+        assert_eq!(second.static_data, Some(saved.clone()));
+
+        // 重新保存工作流定义不会覆盖运行时写回的静态数据
+        wf.name = "renamed".into();
+        storage::save_workflow(&mut pool.get().unwrap(), &mut wf).unwrap();
+        assert_eq!(storage::load_static_data(&mut pool.get().unwrap(), "wf-1").unwrap(), saved);
+
+        // 不记录执行时使用工作流自带的静态数据，修改只体现在运行结果中
+        let unrecorded = wf.execute(&registry).await;
+        assert_eq!(unrecorded.outputs["poll"], json!({ "cursor": 1 }));
+        assert!(wf.static_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resume_execution_skips_completed_nodes() {
+        let pool = memory_pool();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let registry = echo_registry(calls.clone());
+        let mut wf = Workflow::new(Some("wf-1".into()));
+        wf.add_node(Node::new("first", "echo"));
+        wf.add_node(Node::new("second", "echo").with_custom_config(json!({ "fail_once": true })));
+        wf.connect_nodes("first", "second").unwrap();
+
+        let options = ExecutionOptions::default().with_recorder(ExecutionRecorder::new(pool.clone()));
+        let failed = wf.execute_with_options(&registry, &options).await;
+        assert!(!failed.is_success());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let failed_id = failed.execution_id.unwrap();
+        let resumed = resume_execution(&pool, &registry, &failed_id, ExecutionOptions::default()).await;
+        assert!(resumed.is_success(), "{:?}", resumed.error);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(resumed.node_runs["first"].reused);
+        assert_eq!(resumed.outputs["second"], json!({ "echo": { "echo": {} } }));
+
+        let (execution, data) = load_execution(&pool, resumed.execution_id.as_deref().unwrap()).unwrap();
+        assert_eq!(execution.mode, "retry");
+        assert!(execution.finished);
+        assert!(data.nodes["first"].run.reused);
+
+        let again = resume_execution(&pool, &registry, &execution.id.unwrap(), ExecutionOptions::default()).await;
+        assert!(again.error.unwrap().message.contains("already finished"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_execution_uses_latest_definition() {
+        let pool = memory_pool();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let registry = echo_registry(calls.clone());
+        let recorder = ExecutionRecorder::new(pool.clone());
+        let options = ExecutionOptions::default().with_recorder(recorder);
+        let failed = echo_workflow(true).execute_with_options(&registry, &options).await;
+        let failed_id = failed.execution_id.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 按原定义恢复仍然失败
+        let resumed = resume_execution(&pool, &registry, &failed_id, ExecutionOptions::default()).await;
+        assert_eq!(resumed.error.unwrap().node.as_deref(), Some("second"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let mut fixed = echo_workflow(false);
+        fixed.add_node(Node::new("third", "echo"));
+        fixed.connect_nodes("second", "third").unwrap();
+        let retried = retry_execution(&pool, &registry, &failed_id, &fixed, ExecutionOptions::default()).await;
+        assert!(retried.is_success());
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert!(retried.node_runs["first"].reused);
+        assert!(retried.outputs.contains_key("third"));
+
+        assert!(resume_execution(&pool, &registry, "missing", ExecutionOptions::default())
+            .await
+            .error
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rerun_refuses_running_and_waiting_executions() {
+        let pool = memory_pool();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let registry = echo_registry(calls.clone());
+        let wf = echo_workflow(false);
+        let session = ExecutionRecorder::new(pool.clone()).start(&wf).await.unwrap();
+        let execution_id = session.id().to_string();
+
+        let running = resume_execution(&pool, &registry, &execution_id, ExecutionOptions::default()).await;
+        assert!(running.error.unwrap().message.contains("is still running"));
+
+        let waiting = RunResult {
+            waiting: Some(WaitingState { nodes: vec!["second".into()], wait_till: Utc::now() }),
+            ..Default::default()
+        };
+        session.finish(&waiting).await;
+        let retried = retry_execution(&pool, &registry, &execution_id, &wf, ExecutionOptions::default()).await;
+        assert!(retried.error.unwrap().message.contains("is waiting"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_execution_can_be_resumed() {
+        let pool = memory_pool();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let registry = echo_registry(calls.clone());
+        let wf = echo_workflow(false);
+
+        // 模拟进程在 first 完成后退出：执行记录停留在运行中，没有 stopped_at
+        let session = ExecutionRecorder::new(pool.clone()).start(&wf).await.unwrap();
+        let execution_id = session.id().to_string();
+        drop(session);
+        let first = NodeExecutionData {
+            input: Some(json!({})),
+            output: Some(json!({ "echo": {} })),
+            ..Default::default()
+        };
+        let data = ExecutionData {
+            nodes: HashMap::from([("first".to_string(), first)]),
+            workflow: Some(wf.clone()),
+            ..Default::default()
+        };
+        let data = serde_json::to_string(&data).unwrap();
+        execution_ops::update_execution_data(&mut pool.get().unwrap(), &execution_id, &data).unwrap();
+
+        // 最近更新过的执行仍视为运行中
+        assert_eq!(mark_interrupted_executions(&pool, Duration::from_secs(3600)).unwrap(), 0);
+        let running = resume_execution(&pool, &registry, &execution_id, ExecutionOptions::default()).await;
+        assert!(running.error.unwrap().message.contains("is still running"));
+
+        assert_eq!(mark_interrupted_executions(&pool, Duration::ZERO).unwrap(), 1);
+        let (execution, _) = load_execution(&pool, &execution_id).unwrap();
+        assert!(!execution.finished);
+        assert!(execution.stopped_at.is_some());
+
+        let resumed = resume_execution(&pool, &registry, &execution_id, ExecutionOptions::default()).await;
+        assert!(resumed.is_success(), "{:?}", resumed.error);
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "only second runs");
+        assert!(resumed.node_runs["first"].reused);
+        assert_eq!(resumed.outputs["second"], json!({ "echo": { "echo": {} } }));
+    }
+}
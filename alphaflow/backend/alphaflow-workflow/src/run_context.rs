@@ -0,0 +1,92 @@
+// src/run_context.rs
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use crate::global_state::get_global_state;
+use crate::workflow::Workflow;
+
+/// 一次运行范围内共享的上下文，运行开始时构造一次
+///
+/// 节点通过 `NodeExecutionContext` 的 `globals` / `env` 字段访问，
+/// 表达式中对应 `$globals` / `$env` 变量（见 [`crate::expression`]）。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunContext {
+    /// 运行信息：`workflow_id`、`execution_id`、`started_at`（RFC 3339）、
+    /// `timezone`（工作流 settings 中的 `timezone`，缺失时使用 [`crate::global_state::GlobalState`] 的默认时区）
+    /// 以及工作流的 `settings`
+    pub globals: Value,
+    /// 白名单中的环境变量：变量名 -> 值，未设置的变量不出现
+    pub env: Value,
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self {
+            globals: json!({}),
+            env: json!({}),
+        }
+    }
+}
+
+impl RunContext {
+    /// 为一次运行构造上下文，`env_vars` 为允许节点读取的环境变量名
+    pub fn new(
+        workflow: &Workflow,
+        execution_id: Option<&str>,
+        started_at: DateTime<Utc>,
+        env_vars: &[String],
+    ) -> Self {
+        let timezone = workflow
+            .settings
+            .get("timezone")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| get_global_state().default_timezone);
+        let globals = json!({
+            "workflow_id": workflow.id,
+            "execution_id": execution_id,
+            "started_at": started_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "timezone": timezone,
+            "settings": workflow.settings,
+        });
+        Self { globals, env: whitelisted_env(env_vars) }
+    }
+}
+
+/// 读取白名单中的环境变量，未设置或不是合法 UTF-8 的变量被忽略
+pub fn whitelisted_env(names: &[String]) -> Value {
+    let vars: Map<String, Value> = names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok().map(|value| (name.clone(), Value::String(value))))
+        .collect();
+    Value::Object(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_run_context_globals_and_env() {
+        std::env::set_var("AF_RUN_CONTEXT_TEST_VISIBLE", "yes");
+        std::env::set_var("AF_RUN_CONTEXT_TEST_HIDDEN", "secret");
+        let mut workflow = Workflow::new(Some("wf-1".into()));
+        workflow.settings = json!({ "timezone": "Asia/Shanghai", "max_parallelism": 2 });
+        let started_at = Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap();
+
+        let run = RunContext::new(
+            &workflow,
+            Some("exec-1"),
+            started_at,
+            &["AF_RUN_CONTEXT_TEST_VISIBLE".to_string(), "AF_RUN_CONTEXT_TEST_UNSET".to_string()],
+        );
+
+        assert_eq!(run.globals["workflow_id"], json!("wf-1"));
+        assert_eq!(run.globals["execution_id"], json!("exec-1"));
+        assert_eq!(run.globals["started_at"], json!("2025-03-01T08:00:00.000Z"));
+        assert_eq!(run.globals["timezone"], json!("Asia/Shanghai"));
+        assert_eq!(run.globals["settings"]["max_parallelism"], json!(2));
+        assert_eq!(run.env, json!({ "AF_RUN_CONTEXT_TEST_VISIBLE": "yes" }));
+    }
+}
@@ -0,0 +1,116 @@
+// src/run_result.rs
+
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use alphaflow_nodes::node_type::{NodeError, NodeErrorKind};
+use alphaflow_nodes::static_data::StaticData;
+
+/// 节点的一次执行尝试
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeAttempt {
+    /// 第几次执行，从 1 开始
+    pub attempt: u32,
+    /// 逐项执行时对应的输入数据项下标
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<usize>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// 本次执行失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 单个节点在一次运行中的执行记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NodeRunRecord {
+    /// 按时间顺序排列的所有执行尝试
+    pub attempts: Vec<NodeAttempt>,
+    /// 节点最终失败时的错误（包括按 on_error 配置继续执行的情况）
+    #[serde(default)]
+    pub error: Option<RunError>,
+    /// 节点没有执行，输出来自 Pin 数据
+    #[serde(default)]
+    pub pinned: bool,
+    /// 节点没有执行，输出沿用之前的运行结果
+    #[serde(default)]
+    pub reused: bool,
+    /// 传出数据的输出名称
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+}
+
+/// 导致运行失败的错误
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunError {
+    /// 出错的节点，结构校验等运行前的错误为空
+    pub node: Option<String>,
+    pub kind: NodeErrorKind,
+    pub message: String,
+}
+
+impl RunError {
+    /// 由节点错误构造
+    pub fn from_node_error(node: Option<&str>, err: &NodeError) -> Self {
+        Self {
+            node: node.map(String::from),
+            kind: err.kind(),
+            message: err.message().to_string(),
+        }
+    }
+
+    /// 转换回 NodeError
+    pub fn to_node_error(&self) -> NodeError {
+        NodeError::from_kind(self.kind, self.message.clone())
+    }
+}
+
+/// 运行在 Wait 节点处暂停时的等待信息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WaitingState {
+    /// 暂停的 Wait 节点
+    pub nodes: Vec<String>,
+    /// 恢复运行的时间：多个 Wait 节点同时暂停时取最晚的时间，
+    /// 等待外部恢复时为 [`crate::constants::WAIT_INDEFINITELY`]
+    pub wait_till: DateTime<Utc>,
+}
+
+/// 一次工作流运行的完整结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunResult {
+    /// 节点 ID -> 输出数据（只包含成功执行的节点）
+    pub outputs: HashMap<String, Value>,
+    /// 节点 ID -> 执行记录（只包含启动过的节点）
+    pub node_runs: HashMap<String, NodeRunRecord>,
+    /// 运行失败时的错误
+    pub error: Option<RunError>,
+    /// 开启执行记录时，对应 executions 表中的记录 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    /// 运行在 Wait 节点处暂停（尚未结束）时的等待信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting: Option<WaitingState>,
+    /// 运行中静态数据被修改时为修改后的完整数据，调用方可以据此更新内存中的工作流
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_data: Option<StaticData>,
+}
+
+impl RunResult {
+    /// 运行是否成功
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// 运行是否在 Wait 节点处暂停，需要恢复后才会结束
+    pub fn is_waiting(&self) -> bool {
+        self.waiting.is_some()
+    }
+
+    /// 成功时返回各节点输出，失败时返回错误
+    pub fn into_outputs(self) -> Result<HashMap<String, Value>, NodeError> {
+        match self.error {
+            Some(err) => Err(err.to_node_error()),
+            None => Ok(self.outputs),
+        }
+    }
+}
@@ -0,0 +1,465 @@
+// src/storage.rs
+
+//! 运行时 [`Workflow`] 与 sqlite `workflows` 表之间的转换。
+//!
+//! 各 JSON 列的格式：
+//!
+//! - `nodes`：节点数组，每个元素是序列化后的 [`Node`]，`name` 在工作流内唯一，按名称排序保存；
+//!   ```json
+//!   [{ "name": "fetch", "node_type_name": "http", "parameters": { "url": "..." } }]
+//!   ```
+//! - `connections`：连接数组，按源节点分组、保持每个源节点的连接顺序；
+//!   `output` 缺省为 `"main"`，`input` 为该连接在目标节点父节点（输入）中的位置，缺省时按数组中出现的顺序；
+//!   ```json
+//!   [{ "source": "check", "output": "true", "target": "notify", "input": 0 }]
+//!   ```
+//! - `settings`：工作流级别配置对象（见 [`crate::executor::ExecutionOptions::from_settings`]），可为空；
+//! - `static_data`：跨运行保留的静态数据（见 [`StaticData`]），没有数据时为空；
+//!   只在新建工作流时写入，之后由引擎在运行结束后单独写回（见 [`save_static_data`]），
+//!   [`save_workflow`] 更新已有的工作流时不会覆盖；
+//!   ```json
+//!   { "global": { "last_run": "..." }, "nodes": { "poll": { "cursor": 42 } } }
+//!   ```
+//! - `meta`：其他元数据对象，可为空；[`Workflow`] 只使用其中的 `pin_data`（节点 ID -> Pin 数据），
+//!   [`save_workflow`] 更新已有的工作流时只替换 `pin_data`，保留其他键。
+
+use std::collections::{HashMap, HashSet};
+use diesel::result::Error as DieselError;
+use diesel::sqlite::SqliteConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use alphaflow_nodes::node::Node;
+use alphaflow_nodes::node_type::MAIN_OUTPUT;
+use alphaflow_nodes::static_data::StaticData;
+use alphaflow_sqlite::db::workflow_ops;
+use alphaflow_sqlite::models::workflow::{NewWorkflow, Workflow as WorkflowModel};
+use crate::workflow::Workflow;
+
+/// `connections` 列中的一条连接
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredConnection {
+    pub source: String,
+    #[serde(default = "main_output")]
+    pub output: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<usize>,
+}
+
+fn main_output() -> String {
+    MAIN_OUTPUT.to_string()
+}
+
+/// 转换或存取工作流时的错误
+#[derive(Debug, thiserror::Error)]
+pub enum WorkflowRowError {
+    #[error("Invalid JSON in column '{column}': {source}")]
+    InvalidJson {
+        column: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid workflow: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DieselError),
+}
+
+/// `workflows` 表一行的列数据（不含由数据库维护的时间戳）
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowRow {
+    pub id: Option<String>,
+    pub name: String,
+    pub active: bool,
+    pub nodes: String,
+    pub connections: String,
+    pub settings: Option<String>,
+    pub static_data: Option<String>,
+    pub meta: Option<String>,
+    pub owner_id: Option<String>,
+}
+
+impl WorkflowRow {
+    /// 借用为可写入数据库的 [`NewWorkflow`]
+    pub fn as_new_workflow(&self) -> NewWorkflow<'_> {
+        NewWorkflow {
+            id: self.id.as_deref(),
+            name: &self.name,
+            active: self.active,
+            nodes: &self.nodes,
+            connections: &self.connections,
+            settings: self.settings.as_deref(),
+            static_data: self.static_data.as_deref(),
+            meta: self.meta.as_deref(),
+            owner_id: self.owner_id.as_deref(),
+        }
+    }
+}
+
+impl TryFrom<&Workflow> for WorkflowRow {
+    type Error = WorkflowRowError;
+
+    fn try_from(wf: &Workflow) -> Result<Self, Self::Error> {
+        let errors = dangling_connections(wf);
+        if !errors.is_empty() {
+            return Err(WorkflowRowError::Invalid(errors));
+        }
+
+        let mut nodes: Vec<&Node> = wf.nodes.values().collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut sources: Vec<&String> = wf.connections_by_source.keys().collect();
+        sources.sort();
+        let connections: Vec<StoredConnection> = sources
+            .into_iter()
+            .flat_map(|source| {
+                wf.connections_by_source[source].iter().map(move |c| {
+                    let input = wf.connections_by_destination.get(&c.node).and_then(|parents| {
+                        parents.iter().position(|p| p.node == *source && p.output == c.output)
+                    });
+                    StoredConnection {
+                        source: source.clone(),
+                        output: c.output.clone(),
+                        target: c.node.clone(),
+                        input,
+                    }
+                })
+            })
+            .collect();
+
+        let settings = match &wf.settings {
+            Value::Null => None,
+            settings => Some(to_json("settings", settings)?),
+        };
+        let static_data = if wf.static_data.is_empty() {
+            None
+        } else {
+            Some(to_json("static_data", &wf.static_data)?)
+        };
+        let meta = merge_meta(None, &wf.pin_data)?;
+
+        Ok(WorkflowRow {
+            id: wf.id.clone(),
+            name: wf.name.clone(),
+            active: wf.active,
+            nodes: to_json("nodes", &nodes)?,
+            connections: to_json("connections", &connections)?,
+            settings,
+            static_data,
+            meta,
+            owner_id: None,
+        })
+    }
+}
+
+impl TryFrom<&WorkflowModel> for Workflow {
+    type Error = WorkflowRowError;
+
+    fn try_from(model: &WorkflowModel) -> Result<Self, Self::Error> {
+        let nodes: Vec<Node> = from_json("nodes", &model.nodes)?;
+        let connections: Vec<StoredConnection> = from_json("connections", &model.connections)?;
+        let settings: Value = match &model.settings {
+            Some(settings) => from_json("settings", settings)?,
+            None => json!({}),
+        };
+        let static_data: StaticData = match &model.static_data {
+            Some(static_data) => from_json("static_data", static_data)?,
+            None => StaticData::default(),
+        };
+        let meta: Value = match &model.meta {
+            Some(meta) => from_json("meta", meta)?,
+            None => Value::Null,
+        };
+
+        let mut errors = Vec::new();
+        if !settings.is_object() {
+            errors.push("settings must be a JSON object".to_string());
+        }
+        let mut names = HashSet::new();
+        for node in &nodes {
+            if node.name.is_empty() {
+                errors.push(format!("Node of type {} has an empty name", node.node_type_name));
+            } else if !names.insert(node.name.as_str()) {
+                errors.push(format!("Duplicate node name {}", node.name));
+            }
+        }
+
+        let mut wf = Workflow::new(model.id.clone()).with_name(&model.name);
+        wf.active = model.active;
+        wf.settings = settings;
+        wf.static_data = static_data;
+        for node in nodes {
+            wf.add_node(node);
+        }
+        let mut input_positions: HashMap<(&str, &str, &str), usize> = HashMap::new();
+        let mut seen_inputs: HashMap<&str, usize> = HashMap::new();
+        for conn in &connections {
+            if let Err(e) = wf.connect_nodes_on_output(&conn.source, &conn.output, &conn.target) {
+                errors.push(format!("Connection {} -> {}: {}", conn.source, conn.target, e));
+            }
+            let appearance = seen_inputs.entry(conn.target.as_str()).or_default();
+            let position = conn.input.unwrap_or(*appearance);
+            *appearance += 1;
+            input_positions.insert((&conn.target, &conn.source, &conn.output), position);
+        }
+        for (target, parents) in wf.connections_by_destination.iter_mut() {
+            parents.sort_by_key(|p| {
+                input_positions.get(&(target.as_str(), p.node.as_str(), p.output.as_str())).copied()
+            });
+        }
+        if let Some(pin_data) = meta.get("pin_data") {
+            match serde_json::from_value::<HashMap<String, Value>>(pin_data.clone()) {
+                Ok(pin_data) => wf.pin_data = pin_data,
+                Err(e) => errors.push(format!("Invalid pin_data in meta: {e}")),
+            }
+        }
+        errors.extend(wf.pin_data.keys().filter(|n| !wf.nodes.contains_key(*n)).map(|n| {
+            format!("Pin data for unknown node {}", n)
+        }));
+
+        if !errors.is_empty() {
+            return Err(WorkflowRowError::Invalid(errors));
+        }
+        Ok(wf)
+    }
+}
+
+impl TryFrom<WorkflowModel> for Workflow {
+    type Error = WorkflowRowError;
+
+    fn try_from(model: WorkflowModel) -> Result<Self, Self::Error> {
+        Workflow::try_from(&model)
+    }
+}
+
+/// 从 `workflows` 表读取并转换工作流
+pub fn load_workflow(conn: &mut SqliteConnection, wf_id: &str) -> Result<Workflow, WorkflowRowError> {
+    let model = workflow_ops::get_workflow_by_id(conn, wf_id)?;
+    Workflow::try_from(model)
+}
+
+/// 保存工作流：记录已存在时更新（保留原有的所有者、静态数据和 `meta` 中 `pin_data` 以外的键），否则新建；没有 ID 的工作流会先分配一个 ID
+pub fn save_workflow(conn: &mut SqliteConnection, wf: &mut Workflow) -> Result<(), WorkflowRowError> {
+    let wf_id = wf.id.get_or_insert_with(|| uuid::Uuid::new_v4().to_string()).clone();
+    let mut row = WorkflowRow::try_from(&*wf)?;
+    match workflow_ops::get_workflow_by_id(conn, &wf_id) {
+        Ok(existing) => {
+            row.owner_id = existing.owner_id;
+            row.meta = merge_meta(existing.meta.as_deref(), &wf.pin_data)?;
+            workflow_ops::update_workflow(conn, &wf_id, &row.as_new_workflow())?;
+        }
+        Err(DieselError::NotFound) => {
+            workflow_ops::create_workflow(conn, &row.as_new_workflow())?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+/// 读取工作流当前保存的静态数据
+pub fn load_static_data(conn: &mut SqliteConnection, wf_id: &str) -> Result<StaticData, WorkflowRowError> {
+    let model = workflow_ops::get_workflow_by_id(conn, wf_id)?;
+    match &model.static_data {
+        Some(static_data) => from_json("static_data", static_data),
+        None => Ok(StaticData::default()),
+    }
+}
+
+/// 只写回工作流的静态数据，不修改工作流定义
+pub fn save_static_data(
+    conn: &mut SqliteConnection,
+    wf_id: &str,
+    static_data: &StaticData,
+) -> Result<(), WorkflowRowError> {
+    let text = if static_data.is_empty() {
+        None
+    } else {
+        Some(to_json("static_data", static_data)?)
+    };
+    workflow_ops::update_workflow_static_data(conn, wf_id, text.as_deref())?;
+    Ok(())
+}
+
+/// 连接中引用了不存在的节点
+fn dangling_connections(wf: &Workflow) -> Vec<String> {
+    let mut errors: Vec<String> = wf
+        .connections_by_source
+        .iter()
+        .flat_map(|(source, targets)| targets.iter().map(move |t| (source, &t.node)))
+        .filter(|(source, target)| !wf.nodes.contains_key(*source) || !wf.nodes.contains_key(*target))
+        .map(|(source, target)| format!("Connection {} -> {} references an unknown node", source, target))
+        .collect();
+    errors.sort();
+    errors
+}
+
+/// 在已有的 `meta` 对象上设置 `pin_data`（没有 Pin 数据时移除），保留其他键；结果为空对象时返回 None
+fn merge_meta(existing: Option<&str>, pin_data: &HashMap<String, Value>) -> Result<Option<String>, WorkflowRowError> {
+    let mut meta: Map<String, Value> = match existing {
+        Some(meta) => from_json("meta", meta)?,
+        None => Map::new(),
+    };
+    if pin_data.is_empty() {
+        meta.remove("pin_data");
+    } else {
+        meta.insert("pin_data".to_string(), json!(pin_data));
+    }
+    if meta.is_empty() {
+        return Ok(None);
+    }
+    to_json("meta", &meta).map(Some)
+}
+
+fn to_json<T: Serialize + ?Sized>(column: &'static str, value: &T) -> Result<String, WorkflowRowError> {
+    serde_json::to_string(value).map_err(|source| WorkflowRowError::InvalidJson { column, source })
+}
+
+fn from_json<T: for<'de> Deserialize<'de>>(column: &'static str, text: &str) -> Result<T, WorkflowRowError> {
+    serde_json::from_str(text).map_err(|source| WorkflowRowError::InvalidJson { column, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use alphaflow_nodes::node::JoinMode;
+    use alphaflow_sqlite::db::user_ops;
+    use alphaflow_sqlite::models::user::NewUser;
+
+    fn model(nodes: &str, connections: &str) -> WorkflowModel {
+        WorkflowModel {
+            id: Some("wf-1".into()),
+            name: "demo".into(),
+            active: true,
+            nodes: nodes.into(),
+            connections: connections.into(),
+            settings: Some(r#"{ "max_parallelism": 2 }"#.into()),
+            static_data: None,
+            meta: None,
+            owner_id: None,
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+        }
+    }
+
+    fn sample_workflow() -> Workflow {
+        let mut wf = Workflow::new(Some("wf-1".into())).with_name("demo");
+        wf.active = true;
+        wf.settings = json!({ "max_parallelism": 2 });
+        for name in ["check", "a", "b"] {
+            wf.add_node(Node::new(name, "delay"));
+        }
+        wf.add_node(Node::new("join", "merge").with_join_mode(JoinMode::WaitAll));
+        wf.connect_nodes_on_output("check", "true", "b").unwrap();
+        wf.connect_nodes_on_output("check", "false", "a").unwrap();
+        // join 的输入顺序是 b、a，与名称顺序相反
+        wf.connect_nodes("b", "join").unwrap();
+        wf.connect_nodes("a", "join").unwrap();
+        wf.pin_node_data("a", json!({ "pinned": true })).unwrap();
+        wf
+    }
+
+    #[test]
+    fn test_round_trip_through_row() {
+        let wf = sample_workflow();
+        let row = WorkflowRow::try_from(&wf).unwrap();
+        let connections: Value = serde_json::from_str(&row.connections).unwrap();
+        assert_eq!(connections[0], json!({ "source": "a", "output": "main", "target": "join", "input": 1 }));
+
+        let mut stored = model(&row.nodes, &row.connections);
+        stored.meta = row.meta.clone();
+        let loaded = Workflow::try_from(stored).unwrap();
+        assert_eq!(loaded, wf);
+        assert_eq!(loaded.get_parents("join"), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_invalid_rows_are_rejected() {
+        let err = Workflow::try_from(model("not json", "[]")).unwrap_err();
+        assert!(matches!(err, WorkflowRowError::InvalidJson { column: "nodes", .. }));
+
+        let nodes = r#"[{ "name": "a", "node_type_name": "delay" }, { "name": "a", "node_type_name": "http" }]"#;
+        let connections = r#"[{ "source": "a", "target": "missing" }]"#;
+        match Workflow::try_from(model(nodes, connections)).unwrap_err() {
+            WorkflowRowError::Invalid(errors) => {
+                assert_eq!(errors.len(), 2);
+                assert!(errors[0].contains("Duplicate node name a"));
+                assert!(errors[1].contains("missing"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let mut wf = sample_workflow();
+        wf.nodes.remove("b");
+        assert!(matches!(WorkflowRow::try_from(&wf), Err(WorkflowRowError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_save_and_load_workflow() {
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .unwrap();
+        let mut conn = pool.get().unwrap();
+        alphaflow_sqlite::run_migrations(&mut conn).unwrap();
+
+        let mut wf = sample_workflow();
+        wf.id = None;
+        wf.static_data.global.insert("runs".into(), json!(1));
+        save_workflow(&mut conn, &mut wf).unwrap();
+        let wf_id = wf.id.clone().unwrap();
+        assert_eq!(load_workflow(&mut conn, &wf_id).unwrap(), wf);
+
+        wf.static_data.nodes.entry("a".into()).or_default().insert("cursor".into(), json!("abc"));
+        save_static_data(&mut conn, &wf_id, &wf.static_data).unwrap();
+        assert_eq!(load_static_data(&mut conn, &wf_id).unwrap(), wf.static_data);
+
+        let static_data = wf.static_data.clone();
+        wf.remove_node("a");
+        wf.active = false;
+        save_workflow(&mut conn, &mut wf).unwrap();
+        let mut loaded = load_workflow(&mut conn, &wf_id).unwrap();
+        assert_eq!(loaded.static_data, static_data, "only save_static_data writes static data");
+        loaded.static_data = wf.static_data.clone();
+        assert_eq!(loaded, wf);
+        assert_eq!(workflow_ops::list_workflows(&mut conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_save_keeps_owner_and_other_meta() {
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .unwrap();
+        let mut conn = pool.get().unwrap();
+        alphaflow_sqlite::run_migrations(&mut conn).unwrap();
+        let user = NewUser { id: Some("user-1"), email: "owner@example.com", password_hash: "x", role: "member" };
+        user_ops::create_user(&mut conn, &user).unwrap();
+
+        let mut wf = sample_workflow();
+        let row = WorkflowRow {
+            owner_id: Some("user-1".into()),
+            meta: Some(r#"{ "tags": ["ops"], "pin_data": { "a": { "pinned": true } } }"#.into()),
+            ..WorkflowRow::try_from(&wf).unwrap()
+        };
+        workflow_ops::create_workflow(&mut conn, &row.as_new_workflow()).unwrap();
+
+        wf.name = "renamed".into();
+        wf.pin_data.clear();
+        save_workflow(&mut conn, &mut wf).unwrap();
+        let stored = workflow_ops::get_workflow_by_id(&mut conn, "wf-1").unwrap();
+        assert_eq!(stored.name, "renamed");
+        assert_eq!(stored.owner_id.as_deref(), Some("user-1"));
+        let meta: Value = serde_json::from_str(stored.meta.as_deref().unwrap()).unwrap();
+        assert_eq!(meta, json!({ "tags": ["ops"] }));
+
+        wf.pin_node_data("b", json!([1])).unwrap();
+        save_workflow(&mut conn, &mut wf).unwrap();
+        let stored = workflow_ops::get_workflow_by_id(&mut conn, "wf-1").unwrap();
+        let meta: Value = serde_json::from_str(stored.meta.as_deref().unwrap()).unwrap();
+        assert_eq!(meta, json!({ "tags": ["ops"], "pin_data": { "b": [1] } }));
+    }
+}
@@ -0,0 +1,371 @@
+// src/sub_workflow.rs
+
+//! Execute Workflow 节点：在当前运行中执行另一个工作流（子工作流）。
+//!
+//! 节点参数（二选一）：
+//!
+//! - `workflow_id`：从 sqlite `workflows` 表加载的工作流 ID，需要 [`SubWorkflowRunner::with_pool`]；
+//! - `workflow`：内联的工作流定义，即序列化后的 [`Workflow`]；其中的 `{{ }}` 表达式不在父工作流中解析。
+//!
+//! 子工作流必须有且只有一个启用的 Execute Workflow Trigger 节点：当前节点的输入数据项作为该触发节点的输出，
+//! 只有从触发节点可达的节点会执行，最后结束的末端节点的输出作为 Execute Workflow 节点的输出。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde_json::Value;
+use alphaflow_nodes::item::NodeItem;
+use alphaflow_nodes::node_type::{NodeError, NodeExecutionContext, NodeOutput, NodeType, WorkflowRunner};
+use alphaflow_nodes::NodeRegistry;
+use crate::constants::{EXECUTE_WORKFLOW_NODE_TYPE, EXECUTE_WORKFLOW_TRIGGER_NODE_TYPE};
+use crate::executor::ExecutionOptions;
+use crate::persistence::DbPool;
+use crate::run_result::RunResult;
+use crate::storage::load_workflow;
+use crate::workflow::Workflow;
+
+/// 子工作流默认的最大嵌套层数
+pub const DEFAULT_MAX_DEPTH: usize = 10;
+
+/// Execute Workflow 节点，通过 [`NodeExecutionContext::workflows`] 执行子工作流
+#[derive(Default)]
+pub struct ExecuteWorkflowNode;
+
+impl ExecuteWorkflowNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for ExecuteWorkflowNode {
+    fn name(&self) -> &str {
+        EXECUTE_WORKFLOW_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "Execute Workflow"
+    }
+
+    async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+        let runner = ctx.workflows.as_ref().ok_or_else(|| {
+            NodeError::InvalidConfig("Sub-workflow execution is not enabled for this run".to_owned())
+        })?;
+        runner.run_workflow(&ctx.parameters, ctx.items.clone()).await
+    }
+}
+
+/// Execute Workflow Trigger 节点：子工作流的入口，原样传出收到的数据项
+#[derive(Default)]
+pub struct ExecuteWorkflowTriggerNode;
+
+impl ExecuteWorkflowTriggerNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for ExecuteWorkflowTriggerNode {
+    fn name(&self) -> &str {
+        EXECUTE_WORKFLOW_TRIGGER_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "Execute Workflow Trigger"
+    }
+
+    async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+        Ok(NodeOutput::from_items(ctx.items.clone()))
+    }
+}
+
+/// 注册 Execute Workflow 和 Execute Workflow Trigger 节点
+pub fn register_sub_workflow_nodes(registry: &mut NodeRegistry) {
+    registry.register(Arc::new(ExecuteWorkflowNode::new()));
+    registry.register(Arc::new(ExecuteWorkflowTriggerNode::new()));
+}
+
+/// 使用同一个注册表执行子工作流，通过 [`ExecutionOptions::with_workflow_runner`] 交给执行器
+#[derive(Clone)]
+pub struct SubWorkflowRunner {
+    registry: Arc<NodeRegistry>,
+    pool: Option<DbPool>,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl std::fmt::Debug for SubWorkflowRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubWorkflowRunner")
+            .field("depth", &self.depth)
+            .field("max_depth", &self.max_depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SubWorkflowRunner {
+    pub fn new(registry: Arc<NodeRegistry>) -> Self {
+        Self {
+            registry,
+            pool: None,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// 允许按 `workflow_id` 从数据库加载子工作流
+    pub fn with_pool(mut self, pool: DbPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// 设置最大嵌套层数，超过时 Execute Workflow 节点执行失败
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 当前的嵌套层数，顶层运行为 0
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// 读取要运行的工作流：内联定义直接解析，`workflow_id` 在阻塞线程池中从数据库读取
+    async fn load(&self, parameters: &Value) -> Result<Workflow, NodeError> {
+        if let Some(inline) = parameters.get("workflow").filter(|w| !w.is_null()) {
+            return serde_json::from_value(inline.clone())
+                .map_err(|e| NodeError::InvalidConfig(format!("Invalid inline workflow: {e}")));
+        }
+        let Some(wf_id) = parameters.get("workflow_id").and_then(Value::as_str) else {
+            return Err(NodeError::InvalidConfig(
+                "Execute Workflow needs either 'workflow_id' or 'workflow'".to_owned(),
+            ));
+        };
+        let pool = self.pool.clone().ok_or_else(|| {
+            NodeError::InvalidConfig(format!("Cannot load workflow {}: no database configured", wf_id))
+        })?;
+        let wf_id = wf_id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool
+                .get()
+                .map_err(|e| NodeError::ExecutionFailed(format!("Failed to get a database connection: {e}")))?;
+            load_workflow(&mut conn, &wf_id)
+                .map_err(|e| NodeError::InvalidConfig(format!("Failed to load workflow {}: {}", wf_id, e)))
+        })
+        .await
+        .map_err(|e| NodeError::ExecutionFailed(format!("Failed to load workflow: {e}")))?
+    }
+}
+
+#[async_trait]
+impl WorkflowRunner for SubWorkflowRunner {
+    async fn run_workflow(&self, parameters: &Value, items: Vec<NodeItem>) -> Result<NodeOutput, NodeError> {
+        if self.depth >= self.max_depth {
+            return Err(NodeError::ExecutionFailed(format!(
+                "Sub-workflows are nested deeper than the maximum depth of {}",
+                self.max_depth
+            )));
+        }
+        let workflow = self.load(parameters).await?;
+        let label = workflow.id.clone().unwrap_or_else(|| workflow.name.clone());
+
+        let mut triggers: Vec<&String> = workflow
+            .nodes
+            .iter()
+            .filter(|(_, n)| n.node_type_name == EXECUTE_WORKFLOW_TRIGGER_NODE_TYPE && !n.disabled)
+            .map(|(id, _)| id)
+            .collect();
+        triggers.sort();
+        let trigger = match triggers.as_slice() {
+            [trigger] => (*trigger).clone(),
+            [] => {
+                return Err(NodeError::InvalidConfig(format!(
+                    "Sub-workflow '{}' has no Execute Workflow Trigger node",
+                    label
+                )))
+            }
+            _ => {
+                return Err(NodeError::InvalidConfig(format!(
+                    "Sub-workflow '{}' has more than one Execute Workflow Trigger node",
+                    label
+                )))
+            }
+        };
+
+        let mut reachable = workflow.to_directed_graph().get_all_children(&trigger);
+        reachable.insert(trigger.clone());
+        let sub = workflow.subworkflow(&reachable);
+
+        let nested = SubWorkflowRunner { depth: self.depth + 1, ..self.clone() };
+        let options = ExecutionOptions::from_settings(&sub.settings).with_workflow_runner(Arc::new(nested));
+        let seeds = HashMap::from([(trigger, NodeOutput::from_items(items))]);
+        let result = sub.execute_seeded(&self.registry, &options, seeds).await;
+
+        if let Some(err) = &result.error {
+            let at = err.node.as_ref().map(|n| format!(" at node '{}'", n)).unwrap_or_default();
+            return Err(NodeError::from_kind(
+                err.kind,
+                format!("Sub-workflow '{}' failed{}: {}", label, at, err.message),
+            ));
+        }
+        Ok(NodeOutput::new(final_output(&sub, &result)))
+    }
+}
+
+/// 最后结束的末端节点（没有子节点）的输出；没有末端节点产生输出时为空数组
+fn final_output(workflow: &Workflow, result: &RunResult) -> Value {
+    let finished_at = |node_id: &str| {
+        result
+            .node_runs
+            .get(node_id)
+            .and_then(|run| run.attempts.iter().map(|a| a.finished_at).max())
+    };
+    result
+        .outputs
+        .iter()
+        .filter(|(node_id, _)| workflow.get_children(node_id).is_empty())
+        .max_by(|(a, _), (b, _)| (finished_at(a), b).cmp(&(finished_at(b), a)))
+        .map(|(_, data)| data.clone())
+        .unwrap_or_else(|| Value::Array(Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use diesel::sqlite::SqliteConnection;
+    use serde_json::json;
+    use alphaflow_nodes::node::{ItemExecutionMode, Node};
+    use crate::storage::save_workflow;
+
+    /// 把每个数据项的 `n` 字段加上参数 `add`
+    struct AddNode;
+
+    #[async_trait]
+    impl NodeType for AddNode {
+        fn name(&self) -> &str {
+            "add"
+        }
+
+        fn display_name(&self) -> &str {
+            "Add"
+        }
+
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            let add = ctx.parameters["add"].as_i64().unwrap_or(0);
+            let items = ctx
+                .items
+                .iter()
+                .map(|item| NodeItem::new(json!({ "n": item.json["n"].as_i64().unwrap_or(0) + add })))
+                .collect();
+            Ok(NodeOutput::from_items(items))
+        }
+    }
+
+    fn registry() -> Arc<NodeRegistry> {
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(AddNode));
+        register_sub_workflow_nodes(&mut registry);
+        Arc::new(registry)
+    }
+
+    fn memory_pool() -> DbPool {
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .unwrap();
+        alphaflow_sqlite::run_migrations(&mut pool.get().unwrap()).unwrap();
+        pool
+    }
+
+    /// trigger -> plus_ten -> plus_one，另有一个不可达的根节点
+    fn child_workflow() -> Workflow {
+        let mut wf = Workflow::new(Some("child".into())).with_name("child");
+        wf.add_node(Node::new("trigger", EXECUTE_WORKFLOW_TRIGGER_NODE_TYPE));
+        wf.add_node(Node::new("plus_ten", "add").with_custom_config(json!({ "add": 10 })));
+        wf.add_node(Node::new("plus_one", "add").with_custom_config(json!({ "add": 1 })));
+        wf.add_node(Node::new("unrelated", "add").with_custom_config(json!({ "add": 100 })));
+        wf.connect_nodes("trigger", "plus_ten").unwrap();
+        wf.connect_nodes("plus_ten", "plus_one").unwrap();
+        wf
+    }
+
+    fn parent_workflow(exec_config: Value) -> Workflow {
+        let mut wf = Workflow::new(Some("parent".into()));
+        wf.add_node(Node::new("start", "add").with_custom_config(json!({ "add": 1 })));
+        wf.add_node(Node::new("exec", EXECUTE_WORKFLOW_NODE_TYPE).with_custom_config(exec_config));
+        wf.connect_nodes("start", "exec").unwrap();
+        wf
+    }
+
+    #[tokio::test]
+    async fn test_execute_inline_sub_workflow() {
+        let registry = registry();
+        let wf = parent_workflow(json!({ "workflow": child_workflow() }));
+
+        let options = ExecutionOptions::default()
+            .with_workflow_runner(Arc::new(SubWorkflowRunner::new(registry.clone())));
+        let results = wf.run_with_options(&registry, &options).await.unwrap();
+        assert_eq!(results["exec"], json!([{ "n": 12 }]));
+
+        // 没有提供 SubWorkflowRunner 时节点执行失败
+        let err = wf.run(&registry).await.unwrap_err();
+        assert!(err.to_string().contains("not enabled"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_inline_sub_workflow_expressions_use_child_data() {
+        let registry = registry();
+        // 子工作流的 plus_ten 逐项执行，加上数据项自身的 n；表达式由父工作流解析时 $json.n 为 null
+        let mut child = child_workflow();
+        let doubled = Node::new("plus_ten", "add")
+            .with_custom_config(json!({ "add": "{{ $json.n }}" }))
+            .with_execution_mode(ItemExecutionMode::RunOnceForEachItem);
+        child.nodes.insert("plus_ten".into(), doubled);
+        let wf = parent_workflow(json!({ "workflow": child }));
+
+        let options = ExecutionOptions::default()
+            .with_workflow_runner(Arc::new(SubWorkflowRunner::new(registry.clone())));
+        let results = wf.run_with_options(&registry, &options).await.unwrap();
+        assert_eq!(results["exec"], json!([{ "n": 3 }]));
+    }
+
+    #[tokio::test]
+    async fn test_execute_stored_sub_workflow_and_depth_limit() {
+        let registry = registry();
+        let pool = memory_pool();
+        let mut child = child_workflow();
+        save_workflow(&mut pool.get().unwrap(), &mut child).unwrap();
+
+        // 调用自身的工作流：trigger -> exec(workflow_id = "recursive")
+        let mut recursive = Workflow::new(Some("recursive".into()));
+        recursive.add_node(Node::new("trigger", EXECUTE_WORKFLOW_TRIGGER_NODE_TYPE));
+        recursive.add_node(
+            Node::new("exec", EXECUTE_WORKFLOW_NODE_TYPE).with_custom_config(json!({ "workflow_id": "recursive" })),
+        );
+        recursive.connect_nodes("trigger", "exec").unwrap();
+        save_workflow(&mut pool.get().unwrap(), &mut recursive).unwrap();
+
+        let runner = SubWorkflowRunner::new(registry.clone()).with_pool(pool).with_max_depth(3);
+        let options = ExecutionOptions::default().with_workflow_runner(Arc::new(runner));
+
+        let results = parent_workflow(json!({ "workflow_id": "child" }))
+            .run_with_options(&registry, &options)
+            .await
+            .unwrap();
+        assert_eq!(results["exec"], json!([{ "n": 12 }]));
+
+        let err = parent_workflow(json!({ "workflow_id": "recursive" }))
+            .run_with_options(&registry, &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("maximum depth of 3"), "{}", err);
+
+        let err = parent_workflow(json!({ "workflow_id": "missing" }))
+            .run_with_options(&registry, &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to load workflow missing"), "{}", err);
+    }
+}
@@ -1,26 +1,43 @@
 use serde_json::{json, Map, Value};
 use std::error::Error;
-use alphaflow_jmes;
+use crate::expression::{self, ExpressionScope};
+use crate::jq;
+
+/// input_path / output_path 使用的查询语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueryLanguage {
+    /// JMESPath 表达式，例如 "body.items[0]"
+    #[default]
+    JmesPath,
+    /// jq 过滤器，例如 ".body.items[] | select(.price > 10)"
+    Jq,
+}
+
 /// 转换配置，类似于 Step Functions 的 InputPath、Parameters、ResultPath、OutputPath。
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TransformationConfig {
-    /// 从输入数据中提取数据的 JMESPath 表达式。例如："body" 或 "body.items[0]"
+    /// 从输入数据中提取数据的查询，使用 `language` 指定的语言。例如 JMESPath 的 "body" 或 "body.items[0]"
     pub input_path: Option<String>,
-    /// 用于重构数据的参数。可以是一个 JSON 对象，会和 input 数据进行浅合并。
+    /// 用于重构数据的参数。可以是一个 JSON 对象，会和 input 数据进行浅合并；
+    /// 其中的 `{{ ... }}` 模板以 input_path 过滤后的数据求值（见 [`crate::expression`]）。
     pub parameters: Option<Value>,
     /// 指定将转换结果嵌入原始输入中的路径，使用点分隔的字符串。例如："body.transformed"
     pub result_path: Option<String>,
-    /// 从最终数据中提取需要输出的部分，例如 "body.transformed" 或 "transformed"（相对于 result_path）
+    /// 从最终数据中提取需要输出的部分的查询，使用 `language` 指定的语言，
+    /// 例如 JMESPath 的 "body.transformed" 或 "transformed"（相对于 result_path）
     pub output_path: Option<String>,
+    /// input_path 与 output_path 的查询语言
+    pub language: QueryLanguage,
 }
 
-/// 使用 jmespath 执行一个查询，并返回查询结果
-fn query_json(input: &Value, query: &str) -> Result<Value, Box<dyn Error>> {
-    let expr = alphaflow_jmes::compile(query)?;
-    let result = expr.search(input)?;
-    // 这里直接将结果转换为 serde_json::Value
-    let json_value = serde_json::to_value(&*result)?;
-    Ok(json_value)
+/// 使用指定的查询语言执行一个查询，并返回查询结果
+fn query_json(input: &Value, query: &str, language: QueryLanguage) -> Result<Value, Box<dyn Error>> {
+    let scope = ExpressionScope::new(input);
+    let result = match language {
+        QueryLanguage::JmesPath => expression::evaluate(query, &scope)?,
+        QueryLanguage::Jq => jq::evaluate(query, &scope)?,
+    };
+    Ok(result)
 }
 
 /// 简单合并两个 JSON 对象（浅合并）：对于相同 key，参数 config 覆盖 input 的值。
@@ -66,13 +83,14 @@ fn set_nested_value(obj: &mut Value, path: &str, new_value: Value) {
 pub fn transform_data(input: &Value, config: &TransformationConfig) -> Result<Value, Box<dyn Error>> {
     // 1. InputPath：过滤输入数据
     let mut transformed = if let Some(ref input_path) = config.input_path {
-        query_json(input, input_path)?
+        query_json(input, input_path, config.language)?
     } else {
         input.clone()
     };
 
     // 2. Parameters：重构数据，浅合并 parameters 对象
     if let Some(ref params) = config.parameters {
+        let params = expression::render(params, &ExpressionScope::new(&transformed))?;
         if let Some(overlay) = params.as_object() {
             if let Some(base_obj) = transformed.as_object_mut() {
                 let merged = merge_json_objects(base_obj.clone(), overlay);
@@ -96,7 +114,7 @@ pub fn transform_data(input: &Value, config: &TransformationConfig) -> Result<Va
         if config.result_path.is_some() && !output_path.contains('.') {
             final_result = transformed.clone();
         } else {
-            final_result = query_json(&final_result, output_path)?;
+            final_result = query_json(&final_result, output_path, config.language)?;
         }
     }
     Ok(final_result)
@@ -122,6 +140,7 @@ mod tests {
             parameters: None,
             result_path: None,
             output_path: None,
+            ..Default::default()
         };
 
         let result = transform_data(&input, &config).unwrap();
@@ -149,6 +168,7 @@ mod tests {
             })),
             result_path: None,
             output_path: None,
+            ..Default::default()
         };
 
         let result = transform_data(&input, &config).unwrap();
@@ -160,6 +180,55 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    // 测试 Parameters 中的模板：以过滤后的数据求值
+    #[test]
+    fn test_parameters_templates() {
+        let input = json!({
+            "body": {
+                "city": "Paris",
+                "temperature": 18
+            }
+        });
+        let config = TransformationConfig {
+            input_path: Some("body".to_string()),
+            parameters: Some(json!({
+                "summary": "{{ city }}: {{ temperature }}°C",
+                "warm": "{{ temperature > `15` }}"
+            })),
+            result_path: None,
+            output_path: None,
+            ..Default::default()
+        };
+
+        let result = transform_data(&input, &config).unwrap();
+        assert_eq!(result["summary"], json!("Paris: 18°C"));
+        assert_eq!(result["warm"], json!(true));
+    }
+
+    // 测试使用 jq 作为查询语言
+    #[test]
+    fn test_jq_paths() {
+        let input = json!({
+            "body": {
+                "items": [
+                    { "name": "pen", "price": 3 },
+                    { "name": "lamp", "price": 25 },
+                    { "name": "desk", "price": 120 }
+                ]
+            }
+        });
+        let config = TransformationConfig {
+            input_path: Some("{expensive: [.body.items[] | select(.price > 10) | .name]}".to_string()),
+            parameters: Some(json!({ "count": "{{ length(expensive) }}" })),
+            result_path: Some("body.summary".to_string()),
+            output_path: Some(".body.summary".to_string()),
+            language: QueryLanguage::Jq,
+        };
+
+        let result = transform_data(&input, &config).unwrap();
+        assert_eq!(result, json!({ "expensive": ["lamp", "desk"], "count": 2 }));
+    }
+
     // 测试 ResultPath：将转换结果嵌入原始输入中指定的位置
     #[test]
     fn test_result_path() {
@@ -174,6 +243,7 @@ mod tests {
             parameters: Some(json!({ "note": "cool city" })),
             result_path: Some("body.transformed".to_string()),
             output_path: None,
+            ..Default::default()
         };
 
         let result = transform_data(&input, &config).unwrap();
@@ -206,6 +276,7 @@ mod tests {
             parameters: None,
             result_path: None,
             output_path: Some("city".to_string()),
+            ..Default::default()
         };
 
         let result = transform_data(&input, &config).unwrap();
@@ -229,6 +300,7 @@ mod tests {
             result_path: Some("body.transformed".to_string()),
             // 输出路径指定为 "body.transformed"，这样会提取嵌入后的数据
             output_path: Some("body.transformed".to_string()),
+            ..Default::default()
         };
 
         let result = transform_data(&input, &config).unwrap();
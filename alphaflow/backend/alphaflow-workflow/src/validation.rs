@@ -0,0 +1,376 @@
+// src/validation.rs
+
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
+use alphaflow_nodes::NodeRegistry;
+use crate::directed_graph::DirectedGraph;
+use crate::workflow::Workflow;
+
+/// 诊断的严重程度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// 工作流无法执行，必须修复
+    Error,
+    /// 工作流可以执行，但结果可能不符合预期
+    Warning,
+}
+
+/// 诊断类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// 节点之间存在环
+    Cycle,
+    /// 连接的源节点或目标节点不存在
+    DanglingConnection,
+    /// node_type_name 未在 NodeRegistry 中注册
+    UnknownNodeType,
+    /// 多个节点使用了同一个名称
+    DuplicateName,
+    /// 节点无法从任何起始节点到达，永远不会被执行
+    UnreachableNode,
+}
+
+/// 一条结构校验结果，可直接序列化后交给前端展示
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkflowDiagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    /// 相关的节点名称（例如环上的全部节点、连接两端的节点）
+    pub nodes: Vec<String>,
+    /// 可读的描述信息
+    pub message: String,
+}
+
+impl WorkflowDiagnostic {
+    fn error(kind: DiagnosticKind, nodes: Vec<String>, message: String) -> Self {
+        Self { severity: Severity::Error, kind, nodes, message }
+    }
+
+    fn warning(kind: DiagnosticKind, nodes: Vec<String>, message: String) -> Self {
+        Self { severity: Severity::Warning, kind, nodes, message }
+    }
+
+    /// 是否为错误级别的诊断
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// 对工作流进行结构校验，返回按 (严重程度, 类型, 节点) 排序的诊断列表
+pub fn validate_workflow(workflow: &Workflow, registry: &NodeRegistry) -> Vec<WorkflowDiagnostic> {
+    let mut diagnostics = Vec::new();
+    check_duplicate_names(workflow, &mut diagnostics);
+    check_dangling_connections(workflow, &mut diagnostics);
+    check_node_types(workflow, registry, &mut diagnostics);
+    check_cycles(workflow, &mut diagnostics);
+    check_unreachable(workflow, &mut diagnostics);
+
+    diagnostics.sort_by(|a, b| {
+        (a.severity, a.kind, &a.nodes).cmp(&(b.severity, b.kind, &b.nodes))
+    });
+    diagnostics
+}
+
+/// 节点表以 ID 为 key，但节点自身的 name 可能被直接修改而重复
+fn check_duplicate_names(workflow: &Workflow, diagnostics: &mut Vec<WorkflowDiagnostic>) {
+    let mut by_name: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (node_id, node) in &workflow.nodes {
+        by_name.entry(node.name.as_str()).or_default().insert(node_id.as_str());
+    }
+    for (name, ids) in by_name {
+        if ids.len() > 1 {
+            let ids: Vec<String> = ids.into_iter().map(String::from).collect();
+            diagnostics.push(WorkflowDiagnostic::error(
+                DiagnosticKind::DuplicateName,
+                ids.clone(),
+                format!("Node name '{}' is used by multiple nodes: {}", name, ids.join(", ")),
+            ));
+        }
+    }
+}
+
+fn check_dangling_connections(workflow: &Workflow, diagnostics: &mut Vec<WorkflowDiagnostic>) {
+    let mut edges = BTreeSet::new();
+    for (source, targets) in &workflow.connections_by_source {
+        for target in targets {
+            edges.insert((source.clone(), target.node.clone()));
+        }
+    }
+    for (target, sources) in &workflow.connections_by_destination {
+        for source in sources {
+            edges.insert((source.node.clone(), target.clone()));
+        }
+    }
+    for (source, target) in edges {
+        let missing: Vec<&str> = [source.as_str(), target.as_str()]
+            .into_iter()
+            .filter(|name| !workflow.nodes.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            diagnostics.push(WorkflowDiagnostic::error(
+                DiagnosticKind::DanglingConnection,
+                vec![source.clone(), target.clone()],
+                format!(
+                    "Connection {} -> {} references missing node(s): {}",
+                    source,
+                    target,
+                    missing.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// 未注册的节点类型：启用的节点报错，禁用的节点只给出警告
+fn check_node_types(
+    workflow: &Workflow,
+    registry: &NodeRegistry,
+    diagnostics: &mut Vec<WorkflowDiagnostic>,
+) {
+    for (node_id, node) in &workflow.nodes {
+        if registry.get(&node.node_type_name).is_some() {
+            continue;
+        }
+        let message = format!(
+            "NodeType '{}' not registered for node '{}'",
+            node.node_type_name, node_id
+        );
+        let diagnostic = if node.disabled {
+            WorkflowDiagnostic::warning(DiagnosticKind::UnknownNodeType, vec![node_id.clone()], message)
+        } else {
+            WorkflowDiagnostic::error(DiagnosticKind::UnknownNodeType, vec![node_id.clone()], message)
+        };
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// 去掉循环回边（见 [`Workflow::loop_back_edges`]）后的图：回边由执行器按批次驱动，不视为环
+fn forward_graph(workflow: &Workflow) -> DirectedGraph<String> {
+    let mut graph = workflow.to_directed_graph();
+    for (source, target) in workflow.loop_back_edges() {
+        // 同一对节点可能通过多个输出重复连接，每个连接对应图中的一条边
+        for _ in workflow.outputs_between(&source, &target) {
+            graph.remove_connection(&source, &target);
+        }
+    }
+    graph
+}
+
+/// 使用强连通分量定位环：节点数大于 1 的分量，或者存在自环的单个节点
+fn check_cycles(workflow: &Workflow, diagnostics: &mut Vec<WorkflowDiagnostic>) {
+    let graph = forward_graph(workflow);
+    if graph.is_dag() {
+        return;
+    }
+    for component in graph.find_strongly_connected_components() {
+        let mut nodes: Vec<String> = component.into_iter().cloned().collect();
+        nodes.sort();
+        let is_cycle = nodes.len() > 1
+            || graph.get_direct_children(&nodes[0]).contains(&&nodes[0]);
+        if is_cycle {
+            diagnostics.push(WorkflowDiagnostic::error(
+                DiagnosticKind::Cycle,
+                nodes.clone(),
+                format!("Workflow contains a cycle: {}", nodes.join(" -> ")),
+            ));
+        }
+    }
+}
+
+/// 从启用的起始节点出发，沿启用的节点向下遍历，未被访问到的节点永远不会执行
+fn check_unreachable(workflow: &Workflow, diagnostics: &mut Vec<WorkflowDiagnostic>) {
+    let graph = forward_graph(workflow);
+    let enabled = |name: &str| workflow.nodes.get(name).map(|n| !n.disabled).unwrap_or(false);
+
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = graph
+        .get_root_nodes()
+        .into_iter()
+        .filter(|name| enabled(name))
+        .collect();
+    while let Some(current) = queue.pop_front() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        for child in workflow.get_children(&current) {
+            if enabled(&child) && !visited.contains(&child) {
+                queue.push_back(child);
+            }
+        }
+    }
+
+    let mut unreachable: Vec<&String> = workflow
+        .nodes
+        .iter()
+        .filter(|(name, node)| !node.disabled && !visited.contains(*name))
+        .map(|(name, _)| name)
+        .collect();
+    unreachable.sort();
+    for name in unreachable {
+        diagnostics.push(WorkflowDiagnostic::warning(
+            DiagnosticKind::UnreachableNode,
+            vec![name.clone()],
+            format!("Node '{}' is not reachable from any start node and will never run", name),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alphaflow_nodes::node::Node;
+    use alphaflow_nodes::node_type::{NodeError, NodeExecutionContext, NodeOutput, NodeType};
+    use alphaflow_nodes::split_in_batches::split_in_batches_handler::SplitInBatchesHandler;
+    use alphaflow_nodes::split_in_batches::{DONE_OUTPUT, LOOP_OUTPUT, SPLIT_IN_BATCHES_NODE_TYPE};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    struct NoopNode;
+
+    #[async_trait]
+    impl NodeType for NoopNode {
+        fn name(&self) -> &str {
+            "noop"
+        }
+        fn display_name(&self) -> &str {
+            "No-op"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            Ok(NodeOutput { data: ctx.input_data.clone(), ..Default::default() })
+        }
+    }
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(NoopNode));
+        registry
+    }
+
+    fn workflow(names: &[&str], edges: &[(&str, &str)]) -> Workflow {
+        let mut wf = Workflow::new(None);
+        for name in names {
+            wf.add_node(Node::new(name, "noop"));
+        }
+        for (source, target) in edges {
+            wf.connect_nodes(source, target).unwrap();
+        }
+        wf
+    }
+
+    fn kinds(diagnostics: &[WorkflowDiagnostic]) -> Vec<DiagnosticKind> {
+        diagnostics.iter().map(|d| d.kind).collect()
+    }
+
+    #[test]
+    fn test_valid_workflow_has_no_diagnostics() {
+        let wf = workflow(&["a", "b", "c"], &[("a", "b"), ("a", "c")]);
+        assert!(wf.validate(&registry()).is_empty());
+    }
+
+    #[test]
+    fn test_cycle_is_reported_with_all_members() {
+        let wf = workflow(&["start", "a", "b", "c"], &[("start", "a"), ("a", "b"), ("b", "c"), ("c", "a")]);
+        let diagnostics = wf.validate(&registry());
+        let cycle = diagnostics.iter().find(|d| d.kind == DiagnosticKind::Cycle).unwrap();
+        assert!(cycle.is_error());
+        assert_eq!(cycle.nodes, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_self_loop_and_unreachable_cycle() {
+        let wf = workflow(&["a", "b", "c"], &[("a", "a"), ("b", "c"), ("c", "b")]);
+        let diagnostics = wf.validate(&registry());
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![
+                DiagnosticKind::Cycle,
+                DiagnosticKind::Cycle,
+                DiagnosticKind::UnreachableNode,
+                DiagnosticKind::UnreachableNode,
+                DiagnosticKind::UnreachableNode,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loop_back_edge_is_not_a_cycle() {
+        let mut registry = registry();
+        registry.register(Arc::new(SplitInBatchesHandler::new()));
+        let mut wf = workflow(&["start", "body", "after"], &[]);
+        wf.add_node(Node::new("loop", SPLIT_IN_BATCHES_NODE_TYPE));
+        wf.connect_nodes("start", "loop").unwrap();
+        wf.connect_nodes("body", "loop").unwrap();
+        wf.connect_nodes_on_output("loop", LOOP_OUTPUT, "body").unwrap();
+        wf.connect_nodes_on_output("loop", DONE_OUTPUT, "after").unwrap();
+        assert!(wf.validate(&registry).is_empty());
+
+        // 经过 done 输出回到循环节点的连接不是回边，仍然是环
+        wf.connect_nodes("after", "loop").unwrap();
+        let cycle = wf.validate(&registry).into_iter().find(|d| d.kind == DiagnosticKind::Cycle).unwrap();
+        assert_eq!(cycle.nodes, vec!["after", "loop"]);
+    }
+
+    #[test]
+    fn test_dangling_connection_and_unknown_type() {
+        let mut wf = workflow(&["a"], &[]);
+        wf.add_node(Node::new("b", "missing_type"));
+        wf.connections_by_source.insert("a".into(), vec!["ghost".into()]);
+
+        let diagnostics = wf.validate(&registry());
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![DiagnosticKind::DanglingConnection, DiagnosticKind::UnknownNodeType]
+        );
+        assert_eq!(diagnostics[0].nodes, vec!["a", "ghost"]);
+        assert_eq!(diagnostics[1].nodes, vec!["b"]);
+    }
+
+    #[test]
+    fn test_disabled_unknown_type_is_warning() {
+        let mut wf = workflow(&["a"], &[]);
+        wf.add_node(Node::new("b", "missing_type").disabled());
+        let diagnostics = wf.validate(&registry());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_duplicate_names_and_nodes_behind_disabled_parent() {
+        let mut wf = workflow(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        wf.nodes.get_mut("a").unwrap().disabled = true;
+        wf.nodes.insert("copy".into(), Node::new("c", "noop"));
+
+        let diagnostics = wf.validate(&registry());
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![
+                DiagnosticKind::DuplicateName,
+                DiagnosticKind::UnreachableNode,
+                DiagnosticKind::UnreachableNode,
+            ]
+        );
+        assert_eq!(diagnostics[0].nodes, vec!["c", "copy"]);
+        assert_eq!(diagnostics[1].nodes, vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_refuses_workflow_with_errors() {
+        let wf = workflow(&["a", "b"], &[("a", "b"), ("b", "a")]);
+        match wf.run(&registry()).await {
+            Err(NodeError::InvalidConfig(msg)) => assert!(msg.contains("cycle"), "{}", msg),
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_serialization() {
+        let wf = workflow(&["a"], &[("a", "a")]);
+        let diagnostics = wf.validate(&registry());
+        let value = serde_json::to_value(&diagnostics[0]).unwrap();
+        assert_eq!(value["severity"], "error");
+        assert_eq!(value["kind"], "cycle");
+        assert_eq!(value["nodes"], serde_json::json!(["a"]));
+    }
+}
@@ -0,0 +1,458 @@
+// src/wait.rs
+
+//! Wait 节点：让运行等待一段时间、等待到指定时间，或者等待外部调用恢复。
+//!
+//! 节点参数（`resume` 决定等待方式）：
+//!
+//! - `{ "resume": "time_interval", "amount": 5, "unit": "minutes" }`：等待一段时间，`unit` 默认为秒；
+//! - `{ "resume": "specific_time", "date_time": "2025-01-01T08:00:00Z" }`：等待到指定时间；
+//! - `{ "resume": "external" }`：等待调用 [`resume_waiting_execution`] 恢复，调用时可以传入节点的输出。
+//!
+//! 不超过 [`IN_PROCESS_WAIT_LIMIT`] 的等待直接在节点执行时完成。更长的等待会暂停整个运行：
+//! 暂停的节点及其输入写入执行记录（需要 [`ExecutionOptions::with_recorder`]），
+//! 运行以 [`RunResult::waiting`] 结束，不占用任何任务。
+//! [`run_wait_timers`] 定期把到期的执行交给任务引擎，由 [`ResumeExecutionHandler`] 恢复运行。
+
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+use alphaflow_nodes::node::Node;
+use alphaflow_nodes::node_type::{NodeError, NodeExecutionContext, NodeOutput, NodeType};
+use alphaflow_nodes::NodeRegistry;
+use alphaflow_sqlite::db::execution_ops;
+use queue::task_dispatcher::TaskDispatcher;
+use store::model::{QualityOfService, Task, TaskContent};
+use crate::constants::{WAIT_INDEFINITELY, WAIT_NODE_TYPE};
+use crate::executor::ExecutionOptions;
+use crate::persistence::{resume_waiting_execution, DbPool};
+use crate::run_result::RunResult;
+
+/// 在节点执行时直接完成的最长等待时间，更长的等待会暂停运行
+pub const IN_PROCESS_WAIT_LIMIT: Duration = Duration::from_secs(65);
+
+/// 恢复暂停执行的任务类型（任务引擎中的 handler_id）
+pub const RESUME_EXECUTION_TASK: &str = "af-internal.resumeExecution";
+
+/// 等待时间的单位
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitUnit {
+    #[default]
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+/// Wait 节点的参数
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(tag = "resume", rename_all = "snake_case")]
+pub enum WaitParams {
+    /// 等待一段时间
+    TimeInterval {
+        amount: f64,
+        #[serde(default)]
+        unit: WaitUnit,
+    },
+    /// 等待到指定时间，已经过去的时间不再等待
+    SpecificTime { date_time: DateTime<Utc> },
+    /// 等待外部调用恢复
+    External,
+}
+
+impl WaitParams {
+    /// 解析并校验节点参数
+    pub fn parse(parameters: &Value) -> Result<Self, NodeError> {
+        let params: Self = serde_json::from_value(parameters.clone())
+            .map_err(|e| NodeError::InvalidConfig(format!("Param parse error: {e}")))?;
+        if let WaitParams::TimeInterval { amount, .. } = params {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(NodeError::InvalidConfig(format!("Invalid wait amount: {amount}")));
+            }
+        }
+        Ok(params)
+    }
+
+    /// 从 `now` 开始等待时的恢复时间，等待外部恢复时为 [`WAIT_INDEFINITELY`]
+    pub fn resume_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            WaitParams::TimeInterval { amount, unit } => {
+                let seconds = match unit {
+                    WaitUnit::Seconds => *amount,
+                    WaitUnit::Minutes => amount * 60.0,
+                    WaitUnit::Hours => amount * 3600.0,
+                    WaitUnit::Days => amount * 86400.0,
+                };
+                let wait = chrono::Duration::milliseconds((seconds * 1000.0) as i64);
+                now.checked_add_signed(wait).unwrap_or(*WAIT_INDEFINITELY).min(*WAIT_INDEFINITELY)
+            }
+            WaitParams::SpecificTime { date_time } => *date_time,
+            WaitParams::External => *WAIT_INDEFINITELY,
+        }
+    }
+}
+
+/// 节点需要暂停运行时返回恢复时间：只有等待超过 [`IN_PROCESS_WAIT_LIMIT`] 的 Wait 节点会暂停运行，
+/// 此时运行必须写入执行记录（`durable` 为 true），否则返回错误。`parameters` 为解析表达式后的节点参数
+pub(crate) fn pause_until(
+    node: &Node,
+    parameters: &Value,
+    durable: bool,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, NodeError> {
+    if node.node_type_name != WAIT_NODE_TYPE {
+        return Ok(None);
+    }
+    let params = WaitParams::parse(parameters)?;
+    let resume_at = params.resume_at(now);
+    if (resume_at - now).to_std().unwrap_or_default() <= IN_PROCESS_WAIT_LIMIT {
+        return Ok(None);
+    }
+    if !durable {
+        return Err(NodeError::InvalidConfig(format!(
+            "Node '{}' waits until {}, which requires recording the execution (ExecutionOptions::with_recorder)",
+            node.name, resume_at
+        )));
+    }
+    Ok(Some(resume_at))
+}
+
+/// Wait 节点：等待结束后原样传出输入数据及数据项。
+/// 长时间的等待由执行器暂停运行完成（见模块文档），节点本身只执行不超过 [`IN_PROCESS_WAIT_LIMIT`] 的等待。
+#[derive(Default)]
+pub struct WaitNode;
+
+impl WaitNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NodeType for WaitNode {
+    fn name(&self) -> &str {
+        WAIT_NODE_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "Wait"
+    }
+
+    async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+        let now = Utc::now();
+        let wait = (WaitParams::parse(&ctx.parameters)?.resume_at(now) - now).to_std().unwrap_or_default();
+        if wait > IN_PROCESS_WAIT_LIMIT {
+            return Err(NodeError::InvalidConfig(format!(
+                "Waiting {} s exceeds the in-process limit of {} s; run the node through the workflow executor",
+                wait.as_secs(),
+                IN_PROCESS_WAIT_LIMIT.as_secs()
+            )));
+        }
+        tokio::time::sleep(wait).await;
+        Ok(NodeOutput {
+            data: ctx.input_data.clone(),
+            items: Some(ctx.items.clone()),
+            ..Default::default()
+        })
+    }
+}
+
+/// 注册 Wait 节点
+pub fn register_wait_node(registry: &mut NodeRegistry) {
+    registry.register(Arc::new(WaitNode::new()));
+}
+
+/// 任务引擎中恢复暂停执行的处理器，通过 `TaskDispatcher::register_node` 注册。
+/// 任务内容为执行记录 ID（`TaskContent::Text`），恢复后的运行结果作为输出。
+pub struct ResumeExecutionHandler {
+    pool: DbPool,
+    registry: Arc<NodeRegistry>,
+    options: ExecutionOptions,
+}
+
+impl ResumeExecutionHandler {
+    pub fn new(pool: DbPool, registry: Arc<NodeRegistry>) -> Self {
+        Self { pool, registry, options: ExecutionOptions::default() }
+    }
+
+    /// 恢复运行时使用的基础选项，例如子工作流的执行方式和环境变量白名单；
+    /// 工作流 settings 中的字段仍然覆盖对应的选项（见 [`resume_waiting_execution`]）
+    pub fn with_options(mut self, options: ExecutionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+#[async_trait]
+impl NodeType for ResumeExecutionHandler {
+    fn name(&self) -> &str {
+        RESUME_EXECUTION_TASK
+    }
+
+    fn display_name(&self) -> &str {
+        "Resume Execution"
+    }
+
+    async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+        let execution_id = ctx
+            .parameters
+            .get("execution_id")
+            .or_else(|| ctx.parameters.get("text"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| NodeError::InvalidConfig("Missing execution id".to_owned()))?;
+        let run: RunResult = resume_waiting_execution(
+            &self.pool,
+            &self.registry,
+            execution_id,
+            None,
+            self.options.clone(),
+        )
+        .await;
+        if let Some(err) = &run.error {
+            return Err(err.to_node_error());
+        }
+        let data = serde_json::to_value(&run).map_err(|e| NodeError::ExecutionFailed(e.to_string()))?;
+        Ok(NodeOutput::new(data))
+    }
+}
+
+/// 把 `now` 之前到期的暂停执行加入任务队列，返回加入的执行记录 ID。
+///
+/// 加入队列前会清空执行记录的 wait_till，同一个执行只会被加入一次；
+/// 恢复失败的执行不会再被自动恢复，可以调用 [`resume_waiting_execution`] 手动恢复。
+pub fn enqueue_due_executions(
+    pool: &DbPool,
+    dispatcher: &mut TaskDispatcher,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let due = execution_ops::list_due_executions(&mut conn, now.naive_utc()).map_err(|e| e.to_string())?;
+    let mut enqueued = Vec::new();
+    for execution in due {
+        let (Some(id), Some(wait_till)) = (execution.id, execution.wait_till) else {
+            continue;
+        };
+        let claimed = execution_ops::claim_waiting_execution(&mut conn, &id, wait_till).map_err(|e| e.to_string())?;
+        if claimed == 0 {
+            continue;
+        }
+        let task = Task::new(
+            RESUME_EXECUTION_TASK,
+            dispatcher.next_task_id(),
+            TaskContent::Text(id.clone()),
+            QualityOfService::Background,
+        );
+        dispatcher.add_task(task);
+        enqueued.push(id);
+    }
+    Ok(enqueued)
+}
+
+/// 每隔 `poll_interval` 检查一次到期的暂停执行并交给任务引擎，直到 `cancel_token` 被取消。
+/// 任务引擎需要注册 [`ResumeExecutionHandler`]，并由 `TaskRunner` 运行。
+pub async fn run_wait_timers(
+    pool: DbPool,
+    dispatcher: Arc<RwLock<TaskDispatcher>>,
+    poll_interval: Duration,
+    cancel_token: CancellationToken,
+) {
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        let mut dispatcher = dispatcher.write().await;
+        if let Err(e) = enqueue_due_executions(&pool, &mut dispatcher, Utc::now()) {
+            warn!("Failed to enqueue waiting executions: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use diesel::sqlite::SqliteConnection;
+    use serde_json::json;
+    use alphaflow_nodes::input_mapping::InputMapping;
+    use crate::events::{event_channel, ExecutionEvent};
+    use crate::persistence::{load_execution, ExecutionRecorder};
+    use crate::workflow::Workflow;
+
+    /// 测试用节点：返回 { "input": <输入> }
+    struct EchoNode;
+
+    #[async_trait]
+    impl NodeType for EchoNode {
+        fn name(&self) -> &str {
+            "echo"
+        }
+        fn display_name(&self) -> &str {
+            "Echo"
+        }
+        async fn execute(&self, ctx: &NodeExecutionContext) -> Result<NodeOutput, NodeError> {
+            Ok(NodeOutput::new(json!({ "input": ctx.input_data })))
+        }
+    }
+
+    fn registry() -> Arc<NodeRegistry> {
+        let mut registry = NodeRegistry::new();
+        registry.register(Arc::new(EchoNode));
+        register_wait_node(&mut registry);
+        Arc::new(registry)
+    }
+
+    fn memory_pool() -> DbPool {
+        // 内存数据库只在单个连接内可见，因此连接池只保留一个连接
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .unwrap();
+        alphaflow_sqlite::run_migrations(&mut pool.get().unwrap()).unwrap();
+        pool
+    }
+
+    /// start -> wait -> after
+    fn wait_workflow(params: Value) -> Workflow {
+        let mut wf = Workflow::new(Some("wf-wait".into()));
+        wf.add_node(Node::new("start", "echo"));
+        wf.add_node(Node::new("wait", WAIT_NODE_TYPE).with_custom_config(params));
+        wf.add_node(Node::new("after", "echo"));
+        wf.connect_nodes("start", "wait").unwrap();
+        wf.connect_nodes("wait", "after").unwrap();
+        wf
+    }
+
+    #[test]
+    fn test_resume_at() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let params = WaitParams::parse(&json!({ "resume": "time_interval", "amount": 1.5, "unit": "hours" })).unwrap();
+        assert_eq!(params.resume_at(now), Utc.with_ymd_and_hms(2025, 1, 1, 1, 30, 0).unwrap());
+        let params = WaitParams::parse(&json!({ "resume": "specific_time", "date_time": "2025-02-01T08:00:00Z" })).unwrap();
+        assert_eq!(params.resume_at(now), Utc.with_ymd_and_hms(2025, 2, 1, 8, 0, 0).unwrap());
+        let params = WaitParams::parse(&json!({ "resume": "external" })).unwrap();
+        assert_eq!(params.resume_at(now), *WAIT_INDEFINITELY);
+
+        assert!(WaitParams::parse(&json!({ "resume": "time_interval", "amount": -1 })).is_err());
+        assert!(WaitParams::parse(&json!({ "resume": "never" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_short_wait_runs_in_process() {
+        let wf = wait_workflow(json!({ "resume": "time_interval", "amount": 0.05 }));
+        let result = wf.execute(&registry()).await;
+        assert!(result.is_success(), "{:?}", result.error);
+        assert!(!result.is_waiting());
+        assert_eq!(result.outputs["after"], json!({ "input": { "input": {} } }));
+    }
+
+    #[tokio::test]
+    async fn test_long_wait_is_resumed_by_task_engine() {
+        let pool = memory_pool();
+        let registry = registry();
+        let wf = wait_workflow(json!({ "resume": "time_interval", "amount": 2, "unit": "days" }));
+        let options = ExecutionOptions::default().with_recorder(ExecutionRecorder::new(pool.clone()));
+
+        let result = wf.execute_with_options(&registry, &options).await;
+        assert!(result.is_success(), "{:?}", result.error);
+        let waiting = result.waiting.clone().unwrap();
+        assert_eq!(waiting.nodes, vec!["wait"]);
+        assert!(!result.outputs.contains_key("after"));
+        let execution_id = result.execution_id.unwrap();
+        let (execution, data) = load_execution(&pool, &execution_id).unwrap();
+        assert!(!execution.finished);
+        assert_eq!(execution.wait_till, Some(waiting.wait_till.naive_utc()));
+        assert_eq!(data.waiting, Some(waiting));
+
+        let mut dispatcher = TaskDispatcher::new(Duration::from_secs(10));
+        dispatcher.register_node(ResumeExecutionHandler::new(pool.clone(), registry.clone()));
+        assert!(enqueue_due_executions(&pool, &mut dispatcher, Utc::now()).unwrap().is_empty());
+        let later = Utc::now() + chrono::Duration::days(3);
+        assert_eq!(enqueue_due_executions(&pool, &mut dispatcher, later).unwrap(), vec![execution_id.clone()]);
+        assert!(enqueue_due_executions(&pool, &mut dispatcher, later).unwrap().is_empty());
+        assert!(dispatcher.process_next_task().await.is_some());
+
+        let (execution, data) = load_execution(&pool, &execution_id).unwrap();
+        assert!(execution.finished);
+        assert_eq!(execution.wait_till, None);
+        assert!(data.waiting.is_none());
+        assert_eq!(data.nodes["after"].output, Some(json!({ "input": { "input": {} } })));
+        assert!(!data.nodes["start"].run.reused);
+    }
+
+    #[tokio::test]
+    async fn test_resume_uses_workflow_settings_and_handler_options() {
+        std::env::set_var("AF_WAIT_TEST_REGION", "eu-west");
+        let pool = memory_pool();
+        let registry = registry();
+        let mut wf = wait_workflow(json!({ "resume": "time_interval", "amount": 2, "unit": "days" }));
+        let region = InputMapping::Single("$env.AF_WAIT_TEST_REGION".into());
+        wf.add_node(Node::new("region", "echo").with_input_mapping(region));
+        wf.connect_nodes("wait", "region").unwrap();
+        wf.settings = json!({ "use_pin_data": true });
+        wf.pin_node_data("after", json!({ "pinned": true })).unwrap();
+        let options = ExecutionOptions::from_settings(&wf.settings).with_recorder(ExecutionRecorder::new(pool.clone()));
+        let execution_id = wf.execute_with_options(&registry, &options).await.execution_id.unwrap();
+
+        let mut dispatcher = TaskDispatcher::new(Duration::from_secs(10));
+        let base = ExecutionOptions::default().with_env_vars(["AF_WAIT_TEST_REGION"]);
+        dispatcher.register_node(ResumeExecutionHandler::new(pool.clone(), registry.clone()).with_options(base));
+        let later = Utc::now() + chrono::Duration::days(3);
+        assert_eq!(enqueue_due_executions(&pool, &mut dispatcher, later).unwrap(), vec![execution_id.clone()]);
+        assert!(dispatcher.process_next_task().await.is_some());
+
+        let (execution, data) = load_execution(&pool, &execution_id).unwrap();
+        assert!(execution.finished);
+        assert_eq!(data.nodes["after"].output, Some(json!({ "pinned": true })), "settings of the snapshot apply");
+        assert_eq!(data.nodes["region"].output, Some(json!({ "input": "eu-west" })), "handler options apply");
+    }
+
+    #[tokio::test]
+    async fn test_external_resume_with_payload() {
+        let registry = registry();
+        let wf = wait_workflow(json!({ "resume": "external" }));
+        let result = wf.execute(&registry).await;
+        assert!(result.error.unwrap().message.contains("requires recording the execution"));
+
+        let pool = memory_pool();
+        let (tx, mut rx) = event_channel();
+        let options = ExecutionOptions::default().with_recorder(ExecutionRecorder::new(pool.clone())).with_events(tx);
+        let result = wf.execute_with_options(&registry, &options).await;
+        drop(options);
+        assert_eq!(result.waiting.unwrap().wait_till, *WAIT_INDEFINITELY);
+        let execution_id = result.execution_id.unwrap();
+
+        // 暂停的运行以 RunWaiting 结束，而不是 RunFinished
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        assert_eq!(
+            events[events.len() - 2..],
+            [
+                ExecutionEvent::NodeWaiting { node: "wait".into(), wait_till: *WAIT_INDEFINITELY },
+                ExecutionEvent::RunWaiting { nodes: vec!["wait".into()], wait_till: *WAIT_INDEFINITELY },
+            ]
+        );
+
+        let payload = json!({ "approved": true });
+        let resumed =
+            resume_waiting_execution(&pool, &registry, &execution_id, Some(payload), ExecutionOptions::default())
+                .await;
+        assert!(resumed.is_success(), "{:?}", resumed.error);
+        assert_eq!(resumed.execution_id.as_deref(), Some(execution_id.as_str()));
+        assert_eq!(resumed.outputs["after"], json!({ "input": { "approved": true } }));
+
+        // 已经结束的执行不能再次恢复
+        let again = resume_waiting_execution(&pool, &registry, &execution_id, None, ExecutionOptions::default()).await;
+        assert!(again.error.unwrap().message.contains("is not waiting"));
+    }
+}
@@ -1,12 +1,17 @@
 // src/waiting_queue.rs
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use serde_json::Value;
+use alphaflow_nodes::item::NodeItem;
 
 /// 等待队列：用于收集某个子节点来自多个父节点的数据
 #[derive(Debug, Default)]
 pub struct WaitingQueue {
     /// 子节点名称 -> (父节点名称 -> 父节点输出)
     pub data: HashMap<String, HashMap<String, Value>>,
+    /// 子节点名称 -> 已经结束的父节点（无论是否产生了输出）
+    pub finished_parents: HashMap<String, HashSet<String>>,
+    /// 子节点名称 -> (父节点名称 -> 父节点输出的数据项)
+    pub items: HashMap<String, HashMap<String, Vec<NodeItem>>>,
 }
 
 impl WaitingQueue {
@@ -16,6 +21,31 @@ impl WaitingQueue {
             .entry(child.to_string())
             .or_default()
             .insert(parent.to_string(), output);
+        self.mark_finished(child, parent);
+    }
+
+    /// 添加某个父节点输出及其数据项
+    pub fn add_with_items(&mut self, child: &str, parent: &str, output: Value, items: Vec<NodeItem>) {
+        self.items
+            .entry(child.to_string())
+            .or_default()
+            .insert(parent.to_string(), items);
+        self.add(child, parent, output);
+    }
+
+    /// 记录某个父节点已经结束；父节点被跳过（没有输出）时也需要调用
+    pub fn mark_finished(&mut self, child: &str, parent: &str) {
+        self.finished_parents
+            .entry(child.to_string())
+            .or_default()
+            .insert(parent.to_string());
+    }
+
+    /// 丢弃子节点的全部等待数据（节点需要重新执行时使用，例如循环的下一个批次）
+    pub fn clear(&mut self, child: &str) {
+        self.data.remove(child);
+        self.finished_parents.remove(child);
+        self.items.remove(child);
     }
 
     /// 检查是否已经收集到足够父节点数据
@@ -24,9 +54,73 @@ impl WaitingQueue {
         self.data.get(child).map(|m| m.len() >= required).unwrap_or(false)
     }
 
+    /// 已收到输出的父节点数量
+    pub fn received_count(&self, child: &str) -> usize {
+        self.data.get(child).map(|m| m.len()).unwrap_or(0)
+    }
+
+    /// 已结束的父节点数量（包括没有输出的父节点）
+    pub fn finished_count(&self, child: &str) -> usize {
+        self.finished_parents.get(child).map(|s| s.len()).unwrap_or(0)
+    }
+
     /// 合并多个父节点数据，生成一个 JSON 对象：
     /// { "A": output_from_A, "B": output_from_B, ... }
     pub fn merge(&mut self, child: &str) -> Option<Value> {
+        self.finished_parents.remove(child);
+        self.items.remove(child);
         self.data.remove(child).map(|m| serde_json::json!(m))
     }
-}
\ No newline at end of file
+
+    /// 取出子节点的等待数据，按 `parent_order` 给出的父节点顺序排列，
+    /// 没有输出的父节点会被忽略
+    pub fn take_ordered(&mut self, child: &str, parent_order: &[String]) -> Vec<Value> {
+        self.take_by_position(child, parent_order).into_iter().flatten().collect()
+    }
+
+    /// 取出子节点的等待数据，`parent_order` 中的每个父节点占一项，
+    /// 没有输出的父节点（例如被跳过的分支）为 None
+    pub fn take_by_position(&mut self, child: &str, parent_order: &[String]) -> Vec<Option<Value>> {
+        self.finished_parents.remove(child);
+        let mut received = self.data.remove(child).unwrap_or_default();
+        parent_order.iter().map(|parent| received.remove(parent)).collect()
+    }
+
+    /// 取出子节点收到的数据项，按 `parent_order` 给出的父节点顺序拼接；
+    /// 需要在 [`WaitingQueue::take_ordered`] 之前调用
+    pub fn take_ordered_items(&mut self, child: &str, parent_order: &[String]) -> Vec<NodeItem> {
+        let mut received = self.items.remove(child).unwrap_or_default();
+        parent_order
+            .iter()
+            .filter_map(|parent| received.remove(parent))
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_take_ordered_follows_parent_order() {
+        let mut queue = WaitingQueue::default();
+        queue.add("D", "C", json!("from C"));
+        queue.mark_finished("D", "X");
+        queue.add("D", "B", json!("from B"));
+
+        assert_eq!(queue.received_count("D"), 2);
+        assert_eq!(queue.finished_count("D"), 3);
+        assert!(queue.is_ready("D", 2));
+
+        let order = vec!["B".to_string(), "X".to_string(), "C".to_string()];
+        assert_eq!(queue.take_ordered("D", &order), vec![json!("from B"), json!("from C")]);
+        assert_eq!(queue.finished_count("D"), 0);
+        assert!(!queue.is_ready("D", 1));
+
+        queue.mark_finished("D", "B");
+        queue.add("D", "C", json!("from C"));
+        assert_eq!(queue.take_by_position("D", &order), vec![None, None, Some(json!("from C"))]);
+    }
+}
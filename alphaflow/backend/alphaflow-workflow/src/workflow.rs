@@ -1,28 +1,71 @@
 // src/workflow.rs
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use alphaflow_nodes::node::Node;
-use alphaflow_nodes::node_type::{NodeType, NodeExecutionContext, NodeError};
+use alphaflow_nodes::node_type::{NodeError, NodeOutput, MAIN_OUTPUT};
+use alphaflow_nodes::split_in_batches::{LOOP_OUTPUT, SPLIT_IN_BATCHES_NODE_TYPE};
+use alphaflow_nodes::static_data::StaticData;
 use alphaflow_nodes::NodeRegistry;
-use crate::jmes_runtime::compile_and_search;
-use log::{info, warn, error};
+use crate::directed_graph::DirectedGraph;
+use crate::executor::{begin_run, end_run, execute_workflow_seeded, ExecutionOptions, RunHandle};
+use crate::run_result::{RunError, RunResult};
+use crate::validation::{validate_workflow, WorkflowDiagnostic};
+
+/// 一条连接的一端：对端节点 ID 以及连接所使用的源节点输出名称
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Connection {
+    /// 对端节点 ID（在 connections_by_source 中为目标节点，在 connections_by_destination 中为源节点）
+    pub node: String,
+    /// 源节点的输出名称，例如 "main"、"error"、"true"
+    pub output: String,
+}
+
+impl Connection {
+    pub fn new(node: &str, output: &str) -> Self {
+        Self {
+            node: node.to_string(),
+            output: output.to_string(),
+        }
+    }
+
+    /// 使用默认输出 "main" 的连接
+    pub fn main(node: &str) -> Self {
+        Self::new(node, MAIN_OUTPUT)
+    }
+}
+
+impl From<&str> for Connection {
+    fn from(node: &str) -> Self {
+        Connection::main(node)
+    }
+}
 
 /// 工作流结构，包含节点、连接和全局设置
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Workflow {
     /// 工作流 ID（可选）
     pub id: Option<String>,
+    /// 工作流名称
+    pub name: String,
     /// 节点 ID -> 节点实例（静态配置）
     pub nodes: HashMap<String, Node>,
-    /// 源节点 ID -> 目标节点 ID 列表
-    pub connections_by_source: HashMap<String, Vec<String>>,
-    /// 目标节点 ID -> 源节点 ID 列表（反向关系）
-    pub connections_by_destination: HashMap<String, Vec<String>>,
+    /// 源节点 ID -> 连接列表（目标节点及所用的源节点输出）
+    pub connections_by_source: HashMap<String, Vec<Connection>>,
+    /// 目标节点 ID -> 连接列表（源节点及所用的源节点输出，反向关系）
+    pub connections_by_destination: HashMap<String, Vec<Connection>>,
     /// 工作流是否激活
     pub active: bool,
     /// 工作流级别配置（例如时区等）
     pub settings: Value,
+    /// 节点 ID -> Pin 数据；开启 `use_pin_data` 时这些节点不再执行，直接以 Pin 数据作为输出
+    pub pin_data: HashMap<String, Value>,
+    /// 跨运行保留的静态数据，保存在 `workflows` 表的 `static_data` 列
+    #[serde(skip_serializing_if = "StaticData::is_empty")]
+    pub static_data: StaticData,
 }
 
 impl Workflow {
@@ -30,14 +73,23 @@ impl Workflow {
     pub fn new(id: Option<String>) -> Self {
         Self {
             id,
+            name: String::new(),
             nodes: HashMap::new(),
             connections_by_source: HashMap::new(),
             connections_by_destination: HashMap::new(),
             active: false,
             settings: json!({}),
+            pin_data: HashMap::new(),
+            static_data: StaticData::default(),
         }
     }
 
+    /// 设置工作流名称
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
     // -----------------------------
     // 节点管理
     // -----------------------------
@@ -50,18 +102,30 @@ impl Workflow {
     /// 移除节点并删除相关连接
     pub fn remove_node(&mut self, node_id: &str) {
         self.nodes.remove(node_id);
+        self.pin_data.remove(node_id);
+        self.static_data.nodes.remove(node_id);
         self.connections_by_source.remove(node_id);
         for (_, targets) in self.connections_by_source.iter_mut() {
-            targets.retain(|t| t != node_id);
+            targets.retain(|t| t.node != node_id);
         }
         self.connections_by_destination.remove(node_id);
         for (_, sources) in self.connections_by_destination.iter_mut() {
-            sources.retain(|s| s != node_id);
+            sources.retain(|s| s.node != node_id);
         }
     }
 
-    /// 连接两个节点 (source -> target)
+    /// 使用源节点的默认输出 "main" 连接两个节点 (source -> target)
     pub fn connect_nodes(&mut self, source_id: &str, target_id: &str) -> Result<(), String> {
+        self.connect_nodes_on_output(source_id, MAIN_OUTPUT, target_id)
+    }
+
+    /// 使用源节点的指定输出连接两个节点 (source:output -> target)
+    pub fn connect_nodes_on_output(
+        &mut self,
+        source_id: &str,
+        output: &str,
+        target_id: &str,
+    ) -> Result<(), String> {
         if !self.nodes.contains_key(source_id) {
             return Err(format!("Source node {} not found", source_id));
         }
@@ -70,40 +134,71 @@ impl Workflow {
         }
         self.connections_by_source
             .entry(source_id.to_string())
-            .or_insert_with(Vec::new)
-            .push(target_id.to_string());
+            .or_default()
+            .push(Connection::new(target_id, output));
 
         self.connections_by_destination
             .entry(target_id.to_string())
-            .or_insert_with(Vec::new)
-            .push(source_id.to_string());
+            .or_default()
+            .push(Connection::new(source_id, output));
         Ok(())
     }
 
-    /// 断开两个节点之间的连接
+    /// 断开两个节点之间的所有连接（不区分输出）
     pub fn disconnect_nodes(&mut self, source_id: &str, target_id: &str) {
         if let Some(targets) = self.connections_by_source.get_mut(source_id) {
-            targets.retain(|t| t != target_id);
+            targets.retain(|t| t.node != target_id);
         }
         if let Some(sources) = self.connections_by_destination.get_mut(target_id) {
-            sources.retain(|s| s != source_id);
+            sources.retain(|s| s.node != source_id);
         }
     }
 
     /// 构建反向连接表（仅用于初始化时）
     pub fn build_reverse_connections(&mut self) {
-        let mut reverse_map = HashMap::new();
+        let mut reverse_map: HashMap<String, Vec<Connection>> = HashMap::new();
         for (src, targets) in &self.connections_by_source {
             for t in targets {
                 reverse_map
-                    .entry(t.clone())
-                    .or_insert_with(Vec::new)
-                    .push(src.clone());
+                    .entry(t.node.clone())
+                    .or_default()
+                    .push(Connection::new(src, &t.output));
             }
         }
         self.connections_by_destination = reverse_map;
     }
 
+    // -----------------------------
+    // Pin 数据
+    // -----------------------------
+
+    /// 为节点设置 Pin 数据
+    pub fn pin_node_data(&mut self, node_id: &str, data: Value) -> Result<(), String> {
+        if !self.nodes.contains_key(node_id) {
+            return Err(format!("Node {} not found", node_id));
+        }
+        self.pin_data.insert(node_id.to_string(), data);
+        Ok(())
+    }
+
+    /// 移除节点的 Pin 数据，返回原来的数据
+    pub fn unpin_node_data(&mut self, node_id: &str) -> Option<Value> {
+        self.pin_data.remove(node_id)
+    }
+
+    /// 把节点在某次运行中的实际输出设置为 Pin 数据；
+    /// 节点在该次运行中没有输出，或者输出本身来自 Pin 数据时返回错误
+    pub fn pin_last_output(&mut self, node_id: &str, run: &RunResult) -> Result<(), String> {
+        if run.node_runs.get(node_id).map(|r| r.pinned).unwrap_or(false) {
+            return Err(format!("Output of node {} already comes from pin data", node_id));
+        }
+        let output = run
+            .outputs
+            .get(node_id)
+            .ok_or_else(|| format!("Node {} has no output in this run", node_id))?;
+        self.pin_node_data(node_id, output.clone())
+    }
+
     // -----------------------------
     // 父子节点查询
     // -----------------------------
@@ -112,7 +207,7 @@ impl Workflow {
     pub fn get_children(&self, node_id: &str) -> Vec<String> {
         self.connections_by_source
             .get(node_id)
-            .cloned()
+            .map(|targets| targets.iter().map(|t| t.node.clone()).collect())
             .unwrap_or_default()
     }
 
@@ -120,155 +215,298 @@ impl Workflow {
     pub fn get_parents(&self, node_id: &str) -> Vec<String> {
         self.connections_by_destination
             .get(node_id)
-            .cloned()
+            .map(|sources| sources.iter().map(|s| s.node.clone()).collect())
             .unwrap_or_default()
     }
 
-    // -----------------------------
-    // 工作流执行 (BFS 示例)
-    // -----------------------------
+    /// 获取通过指定输出连接的直接子节点列表
+    pub fn get_children_on_output(&self, node_id: &str, output: &str) -> Vec<String> {
+        self.connections_by_source
+            .get(node_id)
+            .map(|targets| {
+                targets
+                    .iter()
+                    .filter(|t| t.output == output)
+                    .map(|t| t.node.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-    /// 运行工作流，采用 BFS 遍历所有节点进行执行
-    ///
-    /// 执行流程：
-    /// 1. 找到所有没有父节点且未禁用的节点作为起始。
-    /// 2. 对于每个节点：
-    ///    a. 根据节点配置中的 node_type_name，从 NodeRegistry 中查找对应实现。
-    ///    b. 合并所有父节点的输出：
-    ///         - 如果只有一个父节点，则直接使用其输出。
-    ///         - 如果多个，则合并为数组；如果没有，则使用空对象。
-    ///    c. 如果节点配置了 input_mapping，则调用表达式引擎对合并结果进行映射，
-    ///       注意映射表达式应明确引用上游数据中某个字段（例如 "uppercase(@.response)"）。
-    ///    d. 构造 NodeExecutionContext，将节点的 custom_config 作为 parameters 传入（也可调整为 parameters 字段）。
-    ///    e. 调用节点的 execute 方法，记录输出结果。
-    ///    f. 将子节点加入队列继续执行。
-    pub async fn run(&self, registry: &NodeRegistry) -> Result<HashMap<String, Value>, NodeError> {
-        // 1) 找到起始节点（无父节点且未禁用）
-        let mut start_nodes = Vec::new();
-        for (node_id, node_cfg) in &self.nodes {
-            if node_cfg.disabled {
+    /// 获取 source 连接到 target 所使用的全部输出名称
+    pub fn outputs_between(&self, source_id: &str, target_id: &str) -> Vec<String> {
+        self.connections_by_source
+            .get(source_id)
+            .map(|targets| {
+                targets
+                    .iter()
+                    .filter(|t| t.node == target_id)
+                    .map(|t| t.output.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 节点是否为循环节点（见 [`SPLIT_IN_BATCHES_NODE_TYPE`]）
+    pub fn is_loop_node(&self, node_id: &str) -> bool {
+        self.nodes
+            .get(node_id)
+            .is_some_and(|node| node.node_type_name == SPLIT_IN_BATCHES_NODE_TYPE)
+    }
+
+    /// 循环节点的循环体：从其 "loop" 输出出发可以到达的全部节点（不含循环节点本身）
+    pub fn loop_body(&self, loop_id: &str) -> HashSet<String> {
+        let mut body = HashSet::new();
+        let mut queue: VecDeque<String> = self.get_children_on_output(loop_id, LOOP_OUTPUT).into();
+        while let Some(current) = queue.pop_front() {
+            if current == loop_id || !body.insert(current.clone()) {
                 continue;
             }
-            if self.get_parents(node_id).is_empty() {
-                start_nodes.push(node_id.clone());
+            queue.extend(self.get_children(&current));
+        }
+        body
+    }
+
+    /// 循环回边 (source, target)：循环体中的节点连回所属循环节点的连接。
+    /// 执行器通过回边驱动下一个批次，环检测会忽略这些连接
+    pub fn loop_back_edges(&self) -> HashSet<(String, String)> {
+        let mut edges = HashSet::new();
+        for loop_id in self.nodes.keys().filter(|id| self.is_loop_node(id)) {
+            let body = self.loop_body(loop_id);
+            for parent in self.get_parents(loop_id) {
+                if body.contains(&parent) || parent == *loop_id {
+                    edges.insert((parent, loop_id.clone()));
+                }
             }
         }
-        if start_nodes.is_empty() {
-            return Ok(HashMap::new());
+        edges
+    }
+
+    /// 将节点与连接转换为 DirectedGraph（节点数据为节点 ID），
+    /// 指向不存在节点的连接会被忽略
+    pub fn to_directed_graph(&self) -> DirectedGraph<String> {
+        let mut graph = DirectedGraph::new();
+        let mut node_ids: Vec<&String> = self.nodes.keys().collect();
+        node_ids.sort();
+        for node_id in node_ids {
+            graph.add_node(node_id.clone(), node_id.clone());
+        }
+        for (source, targets) in &self.connections_by_source {
+            for target in targets {
+                graph.add_connection(source, &target.node);
+            }
         }
+        graph
+    }
 
-        let mut queue: VecDeque<String> = VecDeque::from(start_nodes);
-        let mut results: HashMap<String, Value> = HashMap::new();
+    /// 只保留指定节点及它们之间连接的子工作流，设置、Pin 数据和静态数据一并复制
+    pub fn subworkflow(&self, node_ids: &HashSet<String>) -> Workflow {
+        let keep = |id: &String| node_ids.contains(id);
+        let filter_connections = |map: &HashMap<String, Vec<Connection>>| {
+            map.iter()
+                .filter(|(id, _)| keep(id))
+                .map(|(id, conns)| {
+                    let conns = conns.iter().filter(|c| keep(&c.node)).cloned().collect();
+                    (id.clone(), conns)
+                })
+                .collect()
+        };
+        Workflow {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|(id, _)| keep(id))
+                .map(|(id, node)| (id.clone(), node.clone()))
+                .collect(),
+            connections_by_source: filter_connections(&self.connections_by_source),
+            connections_by_destination: filter_connections(&self.connections_by_destination),
+            active: self.active,
+            settings: self.settings.clone(),
+            pin_data: self
+                .pin_data
+                .iter()
+                .filter(|(id, _)| keep(id))
+                .map(|(id, data)| (id.clone(), data.clone()))
+                .collect(),
+            // 静态数据完整保留，运行结束后写回时不会丢失其余节点的数据
+            static_data: self.static_data.clone(),
+        }
+    }
 
-        while let Some(current_id) = queue.pop_front() {
-            // 获取当前节点配置，跳过禁用或不存在的节点
-            let node_cfg = match self.nodes.get(&current_id) {
-                Some(n) if !n.disabled => n,
-                _ => continue,
-            };
+    // -----------------------------
+    // 结构校验
+    // -----------------------------
 
-            // 2) 使用 node_type_name 从注册表中查找节点实现
-            let node_impl = match registry.get(&node_cfg.node_type_name) {
-                Some(impl_) => impl_,
-                None => {
-                    let err_msg = format!(
-                        "NodeType '{}' not registered for node '{}'",
-                        node_cfg.node_type_name, current_id
-                    );
-                    error!("{}", err_msg);
-                    return Err(NodeError::InvalidConfig(err_msg));
-                }
-            };
+    /// 对工作流进行结构校验（环、悬空连接、未注册的节点类型、重名节点、不可达节点），
+    /// 返回可序列化的诊断列表，供保存或运行前在前端展示
+    pub fn validate(&self, registry: &NodeRegistry) -> Vec<WorkflowDiagnostic> {
+        validate_workflow(self, registry)
+    }
 
-            // 3) 合并所有父节点的输出
-            let parent_ids = self.get_parents(&current_id);
-            let mut merged_inputs = Vec::new();
-            for pid in parent_ids {
-                if let Some(data) = results.get(&pid) {
-                    merged_inputs.push(data.clone());
-                }
-            }
-            let merged_input = if merged_inputs.len() == 1 {
-                merged_inputs.remove(0)
-            } else if merged_inputs.is_empty() {
-                json!({})
-            } else {
-                json!(merged_inputs)
-            };
+    // -----------------------------
+    // 工作流执行
+    // -----------------------------
 
-            // 4) 执行映射：如果配置了 input_mapping，则对合并后的数据执行映射处理
-            let final_input_data = if let Some(mapping) = &node_cfg.input_mapping {
-                // 构造映射上下文：将合并结果放入 "$json" 字段
-                let ctx_json = json!({ "$json": merged_input });
-                match mapping {
-                    alphaflow_nodes::input_mapping::InputMapping::Single(expr_str) => {
-                        match compile_and_search(expr_str, &ctx_json) {
-                            Ok(mapped) => mapped,
-                            Err(e) => {
-                                let err_msg = format!(
-                                    "Mapping error at node '{}' (expr='{:?}'): {:?}",
-                                    current_id, expr_str, e
-                                );
-                                error!("{}", err_msg);
-                                return Err(NodeError::InvalidConfig(err_msg));
-                            }
-                        }
-                    }
-                    alphaflow_nodes::input_mapping::InputMapping::Multi { fields, defaultValue: _ } => {
-                        let mut mapped_obj = serde_json::Map::new();
-                        for (field, expr_str) in fields {
-                            match compile_and_search(expr_str, &ctx_json) {
-                                Ok(mapped_field) => {
-                                    mapped_obj.insert(field.clone(), mapped_field);
-                                }
-                                Err(e) => {
-                                    let err_msg = format!(
-                                        "Mapping error at node '{}' for field '{}' (expr='{:?}'): {:?}",
-                                        current_id, field, expr_str, e
-                                    );
-                                    error!("{}", err_msg);
-                                    return Err(NodeError::InvalidConfig(err_msg));
-                                }
-                            }
-                        }
-                        Value::Object(mapped_obj)
-                    }
-                }
-            } else {
-                merged_input
-            };
+    /// 运行工作流，执行选项从 `settings` 中读取（见 [`ExecutionOptions::from_settings`]）
+    ///
+    /// 执行流程：
+    /// 1. 找到所有没有父节点的节点作为起始。
+    /// 2. 当一个节点的所有父节点都已结束时，该节点进入就绪状态；
+    ///    所有就绪节点并发执行，同时执行的数量不超过 `max_parallelism`。
+    /// 3. 对于每个节点：
+    ///    - 根据节点配置中的 node_type_name，从 NodeRegistry 中查找对应实现。
+    ///    - 按连接顺序合并所有父节点的输出：只有一个父节点时直接使用其输出；
+    ///      多个时合并为数组；没有时使用空对象。需要按键、按位置合并或连接时使用 Merge 节点，
+    ///      各父节点的原始输出通过 `NodeExecutionContext::inputs` 传入。
+    ///    - 如果节点配置了 input_mapping，则调用表达式引擎对合并结果进行映射，
+    ///      注意映射表达式应明确引用上游数据中某个字段（例如 "uppercase(@.response)"）。
+    ///    - 构造 NodeExecutionContext，将节点的 custom_config 作为 parameters 传入。
+    ///    - 调用节点的 execute 方法，记录输出结果。
+    /// 4. 禁用的节点不会执行；若某节点的所有父节点都没有输出，该节点同样被跳过。
+    /// 5. 开启 `use_pin_data` 时，设置了 Pin 数据的节点不执行，直接以 Pin 数据作为输出。
+    ///
+    /// 运行前会调用 [`Workflow::validate`]，存在错误级别的诊断时直接返回 `InvalidConfig`。
+    pub async fn run(&self, registry: &NodeRegistry) -> Result<HashMap<String, Value>, NodeError> {
+        let options = ExecutionOptions::from_settings(&self.settings);
+        self.run_with_options(registry, &options).await
+    }
 
-            // 5) 构造 NodeExecutionContext
-            // 此处我们使用 custom_config 作为节点执行参数
-            let parameters = node_cfg.custom_config.clone().unwrap_or(Value::Null);
-            let exec_ctx = NodeExecutionContext {
-                parameters,
-                input_data: final_input_data,
-                globals: json!(null),
-                env: json!(null),
-                pin_data: None,
-            };
+    /// 使用指定的执行选项运行工作流
+    pub async fn run_with_options(
+        &self,
+        registry: &NodeRegistry,
+        options: &ExecutionOptions,
+    ) -> Result<HashMap<String, Value>, NodeError> {
+        self.execute_with_options(registry, options).await.into_outputs()
+    }
 
-            // 6) 调用节点实现的 execute 方法
-            let output = match node_impl.execute(&exec_ctx).await {
-                Ok(o) => o,
-                Err(err) => {
-                    error!("Execution error at node '{}': {:?}", current_id, err);
-                    return Err(err);
-                }
+    /// 运行工作流并返回完整的运行结果（各节点输出、每次执行尝试的记录以及错误）
+    pub async fn execute(&self, registry: &NodeRegistry) -> RunResult {
+        let options = ExecutionOptions::from_settings(&self.settings);
+        self.execute_with_options(registry, &options).await
+    }
+
+    /// 使用指定的执行选项运行工作流并返回完整的运行结果
+    pub async fn execute_with_options(
+        &self,
+        registry: &NodeRegistry,
+        options: &ExecutionOptions,
+    ) -> RunResult {
+        self.execute_seeded(registry, options, HashMap::new()).await
+    }
+
+    /// 在后台启动运行，返回可以取消或等待运行结束的 [`RunHandle`]
+    pub fn start(&self, registry: Arc<NodeRegistry>, options: ExecutionOptions) -> RunHandle {
+        RunHandle::spawn(self.clone(), registry, options)
+    }
+
+    /// 从指定节点开始运行：只执行该节点及其所有后代节点，
+    /// 这些节点在执行范围之外的父节点使用 `prior_results` 中的输出（例如上一次运行的结果），不再执行。
+    ///
+    /// 起始节点有父节点但 `prior_results` 中没有任何一个父节点的输出时返回错误；
+    /// 其余在 `prior_results` 中找不到输出的外部父节点视为没有输出。
+    pub async fn run_from(
+        &self,
+        registry: &NodeRegistry,
+        node_id: &str,
+        prior_results: &HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>, NodeError> {
+        self.execute_from(registry, node_id, prior_results).await.into_outputs()
+    }
+
+    /// 与 [`Workflow::run_from`] 相同，但返回完整的运行结果
+    pub async fn execute_from(
+        &self,
+        registry: &NodeRegistry,
+        node_id: &str,
+        prior_results: &HashMap<String, Value>,
+    ) -> RunResult {
+        if !self.nodes.contains_key(node_id) {
+            return invalid_run(format!("Node {} not found", node_id));
+        }
+        let mut targets = self.to_directed_graph().get_all_children(node_id);
+        targets.insert(node_id.to_string());
+
+        let start_parents = self.get_parents(node_id);
+        if !start_parents.is_empty() && !start_parents.iter().any(|p| prior_results.contains_key(p)) {
+            return invalid_run(format!(
+                "No prior output for any parent of node {}: {}",
+                node_id,
+                start_parents.join(", ")
+            ));
+        }
+
+        // 历史输出不知道当时激活了哪些输出，因此从外部父节点的所有连接传出
+        let mut seeds: HashMap<String, NodeOutput> = HashMap::new();
+        let external_parents = targets.iter().flat_map(|target| self.get_parents(target));
+        for parent in external_parents.filter(|parent| !targets.contains(parent)) {
+            let Some(data) = prior_results.get(&parent) else { continue };
+            // connections_by_source 与 connections_by_destination 不一致（例如手工构造的数据）
+            let Some(connections) = self.connections_by_source.get(&parent) else {
+                return invalid_run(format!("Outgoing connections of node {} are missing", parent));
             };
+            let mut outputs: Vec<String> = connections.iter().map(|c| c.output.clone()).collect();
+            outputs.sort();
+            outputs.dedup();
+            seeds.insert(parent, NodeOutput::on_outputs(data.clone(), outputs));
+        }
+        let mut included = targets;
+        included.extend(seeds.keys().cloned());
 
-            // 保存结果
-            results.insert(current_id.clone(), output.data);
+        let options = ExecutionOptions::from_settings(&self.settings);
+        self.subworkflow(&included).execute_seeded(registry, &options, seeds).await
+    }
 
-            // 7) 将当前节点的子节点加入 BFS 队列
-            let children = self.get_children(&current_id);
-            for child in children {
-                queue.push_back(child);
-            }
+    /// 运行到指定节点为止：只执行该节点及其所有祖先节点
+    pub async fn run_until(
+        &self,
+        registry: &NodeRegistry,
+        node_id: &str,
+    ) -> Result<HashMap<String, Value>, NodeError> {
+        self.execute_until(registry, node_id).await.into_outputs()
+    }
+
+    /// 与 [`Workflow::run_until`] 相同，但返回完整的运行结果
+    pub async fn execute_until(&self, registry: &NodeRegistry, node_id: &str) -> RunResult {
+        if !self.nodes.contains_key(node_id) {
+            return invalid_run(format!("Node {} not found", node_id));
+        }
+        let mut included = self.to_directed_graph().get_all_parents(node_id);
+        included.insert(node_id.to_string());
+
+        let options = ExecutionOptions::from_settings(&self.settings);
+        self.subworkflow(&included).execute_with_options(registry, &options).await
+    }
+
+    /// 校验后执行，`seeds` 中的节点视为已经执行完成
+    pub(crate) async fn execute_seeded(
+        &self,
+        registry: &NodeRegistry,
+        options: &ExecutionOptions,
+        seeds: HashMap<String, NodeOutput>,
+    ) -> RunResult {
+        let errors: Vec<String> = self
+            .validate(registry)
+            .into_iter()
+            .filter(|d| d.is_error())
+            .map(|d| d.message)
+            .collect();
+        if !errors.is_empty() {
+            let session = begin_run(self, options).await;
+            return end_run(options, session, invalid_run(errors.join("; "))).await;
         }
+        execute_workflow_seeded(self, registry, options, seeds).await
+    }
+}
 
-        Ok(results)
+/// 运行前发现配置错误时的运行结果
+pub(crate) fn invalid_run(message: String) -> RunResult {
+    let err = NodeError::InvalidConfig(message);
+    RunResult {
+        error: Some(RunError::from_node_error(None, &err)),
+        ..Default::default()
     }
 }
 
@@ -281,6 +519,30 @@ mod tests {
     use serde_json::json;
     use tokio;
 
+    #[test]
+    fn test_connections_keep_output_names() {
+        let mut wf = Workflow::new(None);
+        for name in ["a", "b", "c"] {
+            wf.add_node(Node::new(name, "http"));
+        }
+        wf.connect_nodes("a", "b").unwrap();
+        wf.connect_nodes_on_output("a", "error", "c").unwrap();
+        wf.connect_nodes_on_output("a", "error", "b").unwrap();
+
+        assert_eq!(wf.get_children("a"), vec!["b", "c", "b"]);
+        assert_eq!(wf.get_children_on_output("a", "error"), vec!["c", "b"]);
+        assert_eq!(wf.outputs_between("a", "b"), vec!["main", "error"]);
+        assert_eq!(wf.connections_by_destination["c"], vec![Connection::new("a", "error")]);
+
+        wf.connections_by_destination.clear();
+        wf.build_reverse_connections();
+        assert_eq!(wf.connections_by_destination["b"].len(), 2);
+
+        wf.disconnect_nodes("a", "b");
+        assert_eq!(wf.get_children("a"), vec!["c"]);
+        assert!(wf.get_parents("b").is_empty());
+    }
+
     #[tokio::test]
     async fn test_workflow_run_two_openai_nodes_with_mapping() {
         // 1) 构造 NodeRegistry 并注册所有节点（例如 openai）
@@ -309,6 +571,7 @@ mod tests {
                 "prompt": "Hello from Rust, node 1",
                 "system_content": "You are a helpful assistant."
             })),
+            ..Default::default()
         };
 
         // 4) 创建第二个 OpenAI 节点 ("chat_node_2")
@@ -329,6 +592,7 @@ mod tests {
                 "prompt": "",
                 "system_content": "You are an assistant that echoes input in uppercase."
             })),
+            ..Default::default()
         };
 
         // 5) 将两个节点添加到工作流